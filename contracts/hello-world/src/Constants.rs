@@ -1,12 +1,57 @@
 pub(crate) const SCORE_HISTORY_WEIGHT: i128 = 70;
 pub(crate) const VOTE_HISTORY_WEIGHT: i128 = 30;
-pub(crate) const ONE_HOUR_SECONDS: u32 = 420;
+pub(crate) const ONE_HOUR_SECONDS: u32 = 3600;
+// default economic parameters, the admin can change them on chain
 pub(crate) const TRUST_TOKEN_PERCENTAGE: i128 = 30;
-pub(crate) const LESS_HUNDRED_POINTS: i128 = -100;
 pub(crate) const HUNDRED_POINTS: i128 = 100;
 pub(crate) const FIFTY_PERCENT: i128 = 50;
 pub(crate) const TEN_PERCENT: i128 = 10;
 pub(crate) const TWENTY_PERCENT: i128 = 20;
 pub(crate) const TWENTY_POINTS: i128 = 20;
-pub(crate) const MINUS_TWENTY_POINTS: i128 = -20;
 pub(crate) const FIFTY_POINTS: i128 = 50;
+// fee in percent of the stake taken when a bet is cancelled before kickoff
+pub(crate) const CANCEL_FEE_PERCENTAGE: i128 = 2;
+// time after a reschedule the bettors placed before it can cancel without fee
+pub(crate) const RESCHEDULE_OPT_OUT_WINDOW: u32 = 86_400;
+// time new parameters wait before they can be applied
+pub(crate) const PARAMS_TIMELOCK: u32 = 24 * ONE_HOUR_SECONDS;
+// stake locked from the free stake of a summiter for every game he is selected for
+pub(crate) const STAKE_LOCK_AMOUNT: i128 = 1000;
+// delay before a new signing key or supreme court takes over
+pub(crate) const ROTATION_TIMELOCK: u32 = 24 * ONE_HOUR_SECONDS;
+// the ids of private settings are assigned from here, games and public settings stay below
+pub(crate) const FIRST_SETTING_ID: i128 = 1 << 64;
+// signer id of the admin key, it signs the games of every league
+pub(crate) const ADMIN_SIGNER: u32 = 0;
+// schema of the signed envelope, a new layout gets a new version
+pub(crate) const SIGNATURE_VERSION: u32 = 1;
+// default selection policy, 1 summiter and 3 checkers drawn from the 9 best candidates
+pub(crate) const SELECTION_POOL_SIZE: u32 = 9;
+pub(crate) const SELECTION_CHECKERS: u32 = 3;
+pub(crate) const MAX_SELECTION_POOL: u32 = 20;
+// time the admin has to reveal a committed secret once the game is activated
+pub(crate) const SEED_REVEAL_WINDOW: u32 = 3600;
+// bond the admin pays when committing to a secret, forfeited to the game if the secret is withheld
+pub(crate) const SEED_COMMIT_BOND: i128 = 1000;
+// entries of a leaderboard page, a page that grows over it is split in two
+pub(crate) const LEADERBOARD_PAGE_SIZE: u32 = 32;
+// minimum time a summiter waits between asking to leave and withdrawing the stake
+pub(crate) const UNBOND_COOLDOWN: u32 = 86_400;
+// time the supreme court has to rule once the assessment of a game is over, the stake a game locked is released after it
+pub(crate) const SUPREME_COURT_WINDOW: u32 = 7 * 24 * ONE_HOUR_SECONDS;
+pub(crate) const MAX_POINTS: i128 = 1000;
+pub(crate) const MAX_PARLAY_LEGS: u32 = 10;
+// parlay odds are given in hundredths (250 = 2.5x)
+pub(crate) const EVEN_ODDS: i128 = 100;
+// highest odds a parlay pays, its winnings at these odds are reserved from the pool when it is placed
+pub(crate) const MAX_PARLAY_ODDS: i128 = 5000;
+// share in percent of its setting an outcome needs for a parlay leg on it to be priced from the pools, a thinner one pays even odds
+pub(crate) const MIN_LEG_OUTCOME_SHARE: i128 = 5;
+// outcome indexes of the markets with fixed outcomes
+pub(crate) const WINNER_LOCAL: u32 = 0;
+pub(crate) const WINNER_DRAW: u32 = 1;
+pub(crate) const WINNER_AWAY: u32 = 2;
+pub(crate) const TOTALS_OVER: u32 = 0;
+pub(crate) const TOTALS_UNDER: u32 = 1;
+pub(crate) const SIDE_LOCAL: u32 = 0;
+pub(crate) const SIDE_AWAY: u32 = 1;
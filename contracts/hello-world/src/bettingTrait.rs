@@ -1,8 +1,13 @@
 #![no_std]
 
-use soroban_sdk::{contractclient, Address, BytesN, Env, String};
+use soroban_sdk::{contractclient, Address, BytesN, Env, String, Vec};
 
-use crate::types::{AssessmentKey, Bet, BetKey, ClaimType, Game, PrivateBet, ResultGame};
+use crate::types::{
+    AssessmentKey, Bet, BetKey, ClaimPreview, ClaimType, CourtRotation, Game, GameCancellation,
+    GameSchedule, IdKind, LeaderboardIndex, Params, ParamsProposal, Parlay, PrivateBet,
+    PubkeyRotation, PublicBet, Publisher, ResultAssessment, ResultGame, SelectionDraw,
+    SelectionPolicy, SettingPools, SignedEnvelope, SummiterBond, Timeline, TimelineScope,
+};
 
 #[contractclient(name = "BettingClient")]
 pub trait betting {
@@ -15,10 +20,50 @@ pub trait betting {
         supreme_court: Address,
     );
     fn request_result_summiter(env: Env, user: Address, stakeAmount: i128) -> bool;
-    fn bet(env: Env, user: Address, bet: Bet) -> bool;
+    fn bet(env: Env, user: Address, bet: Bet) -> i128;
     fn claim_refund(env: Env, user: Address, setting: i128) -> i128;
-    fn set_game(env: Env, game: Game, signature: BytesN<64>) -> bool;
-    fn set_private_bet(env: Env, user: Address, privateData: PrivateBet, game_id: i128) -> bool;
+    fn set_game(
+        env: Env,
+        game: Game,
+        key_id: u32,
+        envelope: SignedEnvelope,
+        signature: BytesN<64>,
+    ) -> bool;
+    fn publish_fixtures(
+        env: Env,
+        root: BytesN<32>,
+        key_id: u32,
+        envelope: SignedEnvelope,
+        signature: BytesN<64>,
+    ) -> bool;
+    fn set_proven_game(env: Env, root: BytesN<32>, game: Game, proof: Vec<BytesN<32>>) -> bool;
+    fn set_games(env: Env, root: BytesN<32>, games: Vec<Game>, proofs: Vec<Vec<BytesN<32>>>)
+        -> u32;
+    fn add_publisher(
+        env: Env,
+        user: Address,
+        key_id: u32,
+        pubkey: BytesN<32>,
+        leagues: Vec<i128>,
+    ) -> bool;
+    fn revoke_publisher(env: Env, user: Address, key_id: u32) -> bool;
+    fn commit_seed(env: Env, user: Address, game_id: i128, commitment: BytesN<32>) -> bool;
+    fn reveal_seed(env: Env, game_id: i128, secret: BytesN<32>) -> BytesN<32>;
+    fn fallback_seed(env: Env, game_id: i128) -> BytesN<32>;
+    fn cancel_game(
+        env: Env,
+        cancellation: GameCancellation,
+        envelope: SignedEnvelope,
+        signature: BytesN<64>,
+    ) -> bool;
+    fn reschedule_game(
+        env: Env,
+        schedule: GameSchedule,
+        envelope: SignedEnvelope,
+        signature: BytesN<64>,
+    ) -> bool;
+    fn set_private_bet(env: Env, user: Address, privateData: PrivateBet, game_id: i128) -> i128;
+    fn set_public_bet(env: Env, user: Address, publicData: PublicBet, game_id: i128) -> i128;
     fn add_user_privateBet(env: Env, setting: i128, game: i128, newUser: Address) -> bool;
     fn summitResult(env: Env, user: Address, result: ResultGame) -> bool;
     fn assessResult(
@@ -29,7 +74,62 @@ pub trait betting {
         desition: AssessmentKey,
     ) -> bool;
     fn claim(env: Env, user: Address, typeClaim: ClaimType, setting: i128) -> (i128, i128);
+    fn preview_claim(env: Env, user: Address, setting: i128) -> ClaimPreview;
     fn setResult_supremCourt(env: Env, result: ResultGame) -> bool;
     fn execute_distribution(env: Env, gameId: i128, setting: i128) -> bool;
     fn set_stakeAmount(env: Env, user: Address, amount: i128) -> bool;
+    fn set_selection_policy(env: Env, user: Address, policy: SelectionPolicy) -> bool;
+    fn set_stake_lock(env: Env, user: Address, amount: i128) -> bool;
+    fn set_timeline(env: Env, user: Address, scope: TimelineScope, timeline: Timeline) -> bool;
+    fn propose_params(env: Env, user: Address, params: Params) -> u32;
+    fn apply_params(env: Env) -> bool;
+    fn propose_admin(env: Env, user: Address, new_admin: Address) -> bool;
+    fn accept_admin(env: Env, new_admin: Address) -> bool;
+    fn propose_pubkey(env: Env, user: Address, pubkey: BytesN<32>) -> u32;
+    fn apply_pubkey(env: Env) -> bool;
+    fn propose_supreme_court(env: Env, user: Address, supreme_court: Address) -> u32;
+    fn apply_supreme_court(env: Env) -> bool;
+    fn migrate_leaderboard(env: Env, user: Address, limit: u32) -> u32;
+    fn request_unbond(env: Env, user: Address) -> u32;
+    fn withdraw_stake(env: Env, user: Address) -> i128;
+    fn bet_parlay(env: Env, user: Address, parlay: Parlay) -> i128;
+    fn claim_parlay(env: Env, user: Address, parlay: i128) -> i128;
+    fn fund_parlay_pool(env: Env, user: Address, amount: i128) -> bool;
+    fn cancel_bet(env: Env, user: Address, setting: i128, outcome: u32) -> i128;
+    fn update_bet(env: Env, user: Address, setting: i128, outcome: u32, newOutcome: u32) -> bool;
+
+    // read only
+    fn get_game(env: Env, game_id: i128) -> Game;
+    fn get_timeline(env: Env, game_id: i128) -> Timeline;
+    fn get_params(env: Env) -> Params;
+    fn get_params_proposal(env: Env) -> ParamsProposal;
+    fn get_admin(env: Env) -> Address;
+    fn get_pending_admin(env: Env) -> Option<Address>;
+    fn get_admin_pubkey(env: Env) -> BytesN<32>;
+    fn get_pubkey_rotation(env: Env) -> PubkeyRotation;
+    fn get_supreme_court(env: Env) -> Address;
+    fn get_court_rotation(env: Env) -> CourtRotation;
+    fn get_game_params(env: Env, game_id: i128) -> Params;
+    fn is_game_cancelled(env: Env, game_id: i128) -> bool;
+    fn get_stake(env: Env, user: Address) -> i128;
+    fn get_stake_lock(env: Env) -> i128;
+    fn get_publisher(env: Env, key_id: u32) -> Publisher;
+    fn get_id_owner(env: Env, kind: IdKind, id: i128) -> Option<Address>;
+    fn get_selection_policy(env: Env) -> SelectionPolicy;
+    fn get_selection_draw(env: Env, game_id: i128) -> SelectionDraw;
+    fn get_summiter_bond(env: Env, user: Address) -> SummiterBond;
+    fn get_result(env: Env, game_id: i128) -> ResultGame;
+    fn get_assessment(env: Env, game_id: i128) -> ResultAssessment;
+    fn get_game_settings(env: Env, game_id: i128) -> Vec<i128>;
+    fn get_setting(env: Env, setting: i128) -> PrivateBet;
+    fn get_public_setting(env: Env, setting: i128) -> PublicBet;
+    fn get_bet(env: Env, user: Address, setting: i128) -> Vec<Bet>;
+    fn get_honesty_points(env: Env, user: Address) -> i128;
+    fn get_leaderboard(env: Env, start: u32, limit: u32) -> Vec<(Address, i128)>;
+    fn get_leaderboard_index(env: Env) -> LeaderboardIndex;
+    fn get_pools(env: Env, setting: i128) -> SettingPools;
+    fn get_claimable(env: Env, user: Address) -> i128;
+    fn get_parlay(env: Env, user: Address, parlay: i128) -> Parlay;
+    fn get_parlay_pool(env: Env) -> i128;
+    fn get_parlay_reserved(env: Env) -> i128;
 }
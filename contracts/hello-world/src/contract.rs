@@ -6,13 +6,18 @@ use crate::{
     events::BettingEvents,
     storage,
     types::{
-        AssessmentKey, Bet, BetKey, BetType, ClaimType, DataKey, Game, LastB, PrivateBet,
-        PublicBet, ResultAssessment, ResultGame,
+        AssessmentKey, Bet, BetKey, BetType, ClaimPreview, ClaimType, CourtRotation, DataKey, Game,
+        GameCancellation, GameSchedule, HalfTime, IdKind, LastB, LeaderboardIndex, MarketType,
+        Params, ParamsProposal, Parlay, ParlayLeg, PrivateBet, PubkeyRotation, PublicBet,
+        Publisher, ResultAssessment, ResultGame, Score, SelectionDraw, SelectionPolicy,
+        SettingPools, Settlement, SignedEnvelope, SummiterBond, Timeline, TimelineScope, UserKind,
     },
     Constants::{
-        FIFTY_PERCENT, FIFTY_POINTS, HUNDRED_POINTS, LESS_HUNDRED_POINTS, MINUS_TWENTY_POINTS,
-        ONE_HOUR_SECONDS, SCORE_HISTORY_WEIGHT, TEN_PERCENT, TRUST_TOKEN_PERCENTAGE,
-        TWENTY_PERCENT, TWENTY_POINTS, VOTE_HISTORY_WEIGHT,
+        ADMIN_SIGNER, EVEN_ODDS, FIRST_SETTING_ID, MAX_PARLAY_LEGS, MAX_PARLAY_ODDS, MAX_POINTS,
+        MAX_SELECTION_POOL, MIN_LEG_OUTCOME_SHARE, PARAMS_TIMELOCK, RESCHEDULE_OPT_OUT_WINDOW,
+        ROTATION_TIMELOCK, SCORE_HISTORY_WEIGHT, SEED_COMMIT_BOND, SEED_REVEAL_WINDOW, SIDE_AWAY,
+        SIDE_LOCAL, SIGNATURE_VERSION, SUPREME_COURT_WINDOW, TOTALS_OVER, UNBOND_COOLDOWN,
+        VOTE_HISTORY_WEIGHT, WINNER_AWAY, WINNER_DRAW, WINNER_LOCAL,
     },
 };
 use soroban_sdk::{
@@ -64,6 +69,14 @@ impl betting for BettingContract {
         if stakeAmount <= min_stake {
             panic_with_error!(&env, BettingError::NotEnoughStake);
         }
+        let bond: SummiterBond = storage::get_summiterBond(env.clone(), user.clone());
+        if bond.unbonding {
+            panic_with_error!(&env, BettingError::SummiterUnbonding);
+        }
+        // the bond record is what makes an address a summiter
+        if !storage::has_summiterBond(env.clone(), user.clone()) {
+            storage::set_summiterBond(env.clone(), user.clone(), bond);
+        }
         let usd = storage::get_usd(env.clone());
         let contract_address = env.current_contract_address();
         Self::moveToken(&env, &usd, &user, &contract_address, &stakeAmount);
@@ -74,9 +87,10 @@ impl betting for BettingContract {
        @dev this funtion bet on a game
        @param env Environment
        @param user Address The address of the user
-       @param bet Bet The bet data
+       @param bet Bet The bet data, its id is assigned by the contract
+       @return i128 The id of the position of the user on the outcome
     */
-    fn bet(env: Env, user: Address, bet: Bet) -> bool {
+    fn bet(env: Env, user: Address, bet: Bet) -> i128 {
         user.require_auth();
         let contract_address = env.current_contract_address();
         let usd = storage::get_usd(env.clone());
@@ -84,7 +98,7 @@ impl betting for BettingContract {
         if bet.clone().amount_bet <= 0 {
             panic_with_error!(&env, BettingError::InvalidInputError);
         }
-        if bet.clone().id == 0 || bet.clone().Setting == 0 || bet.clone().gameid == 0 {
+        if bet.clone().Setting == 0 || bet.clone().gameid == 0 {
             panic_with_error!(&env, BettingError::InvalidInputError);
         }
         let (exist, startTime, endTime, _, _, active) =
@@ -92,12 +106,37 @@ impl betting for BettingContract {
         if !exist {
             panic_with_error!(&env, BettingError::GameDoesNotExist);
         }
+        if storage::get_gameCancelled(env.clone(), bet.clone().gameid) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
         if startTime < env.ledger().timestamp() as u32 {
             panic_with_error!(&env, BettingError::GameHasAlreadyStarted);
         }
         if storage::CheckUser(env.clone(), user.clone(), bet.clone().gameid) {
             panic_with_error!(&env, BettingError::Summiters_notAllowToBet);
         }
+        let mut firstPosition = false;
+        // a position keeps the id it got when it was opened, topping it up doesn't assign a new one
+        let mut bet = bet;
+        bet.id = match storage::get_Bets(env.clone(), user.clone(), bet.clone().Setting)
+            .iter()
+            .find(|placed| placed.bet == bet.bet)
+        {
+            Some(placed) => {
+                // the stake placed before a reschedule would lose its opt out with the top up
+                if Self::in_opt_out_window(env.clone(), bet.clone().gameid, placed.id) {
+                    panic_with_error!(&env, BettingError::PositionInOptOutWindow);
+                }
+                placed.id
+            }
+            None => {
+                let id = storage::next_id(env.clone(), IdKind::Bet);
+                storage::set_idOwner(env.clone(), IdKind::Bet, id, user.clone());
+                id
+            }
+        };
+        // a top up counts as placing the position again
+        storage::set_betPlacedAt(env.clone(), bet.id, env.ledger().timestamp() as u32);
 
         if bet.clone().betType == BetType::Private {
             let privateBet: PrivateBet = storage::get_PrivateBet(env.clone(), bet.clone().Setting);
@@ -105,13 +144,40 @@ impl betting for BettingContract {
             if privateBet.clone().id == 0 {
                 panic_with_error!(&env, BettingError::SettingBetDoesNotExist);
             }
+            if privateBet.clone().gameid != bet.clone().gameid {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
             if !privateBet.clone().users_invated.contains(&user) {
                 panic_with_error!(&env, BettingError::PrivateBet_NotAllowToBet);
             }
-            if bet.clone().amount_bet != privateBet.clone().amount_bet_min {
-                panic_with_error!(&env, BettingError::PrivateBet_NotEnoughToBet);
+            // the limits apply to the whole stake of the user in the setting
+            let mut held: i128 = 0;
+            let mut position: i128 = 0;
+            for placed in storage::get_Bets(env.clone(), user.clone(), bet.clone().Setting).iter() {
+                held += placed.amount_bet;
+                if placed.bet == bet.clone().bet {
+                    position += placed.amount_bet;
+                }
+            }
+            if privateBet.fixed_stake {
+                // a fixed stake can't be topped up
+                if position != 0 || bet.clone().amount_bet != privateBet.clone().amount_bet_min {
+                    panic_with_error!(&env, BettingError::PrivateBet_NotEnoughToBet);
+                }
+            } else {
+                if bet.clone().amount_bet < privateBet.clone().amount_bet_min {
+                    panic_with_error!(&env, BettingError::PrivateBet_NotEnoughToBet);
+                }
+                if privateBet.amount_bet_max != 0
+                    && held + bet.clone().amount_bet > privateBet.clone().amount_bet_max
+                {
+                    panic_with_error!(&env, BettingError::PrivateBet_TooMuchToBet);
+                }
+            }
+            if bet.clone().bet >= Self::market_outcomes(privateBet.clone().market) {
+                panic_with_error!(&env, BettingError::InvalidInputError);
             }
-            storage::add_bet(env.clone(), user.clone(), bet.clone());
+            firstPosition = storage::add_bet(env.clone(), user.clone(), bet.clone());
             storage::add_not_assesed_yet(
                 env.clone(),
                 bet.clone().Setting,
@@ -129,13 +195,44 @@ impl betting for BettingContract {
                 }
             }
         } else {
-            panic_with_error!(&env, BettingError::InvalidInputError);
+            let publicBet: PublicBet = storage::get_PublicBet(env.clone(), bet.clone().Setting);
+
+            if publicBet.clone().id == 0 {
+                panic_with_error!(&env, BettingError::PublicSettingNotFound);
+            }
+            if publicBet.clone().gameid != bet.clone().gameid {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
+            if bet.clone().bet >= Self::market_outcomes(publicBet.clone().market) {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
+            firstPosition = storage::add_bet(env.clone(), user.clone(), bet.clone());
+            storage::add_not_assesed_yet(
+                env.clone(),
+                bet.clone().Setting,
+                bet.clone().amount_bet,
+                bet.clone().bet,
+            );
+            if !publicBet.active {
+                if storage::does_bet_active(env.clone(), bet.clone()) {
+                    storage::active_public_setting(env.clone(), bet.clone().Setting, true);
+                    BettingEvents::active_setting(&env, publicBet.gameid, publicBet.id);
+                    //if the game hasn't been actived we select the summiter
+                    if active == false {
+                        Self::select_summiter(env.clone(), publicBet.gameid)
+                    }
+                }
+            }
+        }
+        let params: Params = storage::get_game_params(env.clone(), bet.clone().gameid);
+        // a user is counted and charged the points once per setting, whatever his positions are
+        if firstPosition {
+            storage::add_UsersAmount(env.clone(), bet.clone().Setting);
+            storage::add_HonestyPoints(env.clone(), user.clone(), -params.bet_points);
+            let points = storage::get_HonestyPoints(env.clone(), user.clone());
+            BettingEvents::user_honesty_points(&env, user.clone(), points);
         }
-        storage::add_UsersAmount(env.clone(), bet.clone().Setting);
         storage::add_total_bet(env.clone(), bet.clone().gameid, bet.clone().amount_bet);
-        storage::add_HonestyPoints(env.clone(), user.clone(), MINUS_TWENTY_POINTS);
-        let points = storage::get_HonestyPoints(env.clone(), user.clone());
-        BettingEvents::user_honesty_points(&env, user.clone(), points);
         Self::moveToken(
             &env,
             &usd,
@@ -148,14 +245,15 @@ impl betting for BettingContract {
             &trust,
             &user,
             &contract_address,
-            &((bet.clone().amount_bet * TRUST_TOKEN_PERCENTAGE) / 100),
+            &((bet.clone().amount_bet * params.trust_percentage) / 100),
         );
-        true
+        bet.id
     }
     /*
-       @dev This function claim the refund in two conditions
+       @dev This function claim the refund in these conditions
        1. the bet setting has not been activated ( all users have bet on the same )
         2. the game has finished and no result has been summited after 3 hours of the end time
+        3. the admin cancelled the game
        @param env Environment
        @param user Address The address of the user
        @param setting i128 The id of the setting
@@ -166,49 +264,55 @@ impl betting for BettingContract {
         let usd = storage::get_usd(env.clone());
         let trust: Address = storage::get_trust(env.clone());
         let mut totalBet = 0;
-        let betData: Bet = storage::get_bet(env.clone(), user.clone(), setting.clone());
-        if betData.id == 0 {
+        let bets: Vec<Bet> = storage::get_Bets(env.clone(), user.clone(), setting.clone());
+        if bets.is_empty() {
             panic_with_error!(&env, BettingError::NoBetHasBeenFound);
         }
-        let mut amountUsd = betData.clone().amount_bet;
+        // every position of the user in the setting is refunded
+        let betData: Bet = bets.get(0).unwrap();
+        let mut amountBet: i128 = 0;
+        for position in bets.iter() {
+            amountBet += position.amount_bet;
+        }
+        let mut amountUsd = amountBet;
         let receivedResult: ResultGame =
             storage::get_ResultGame(env.clone(), betData.clone().gameid);
         let finesApplied = storage::get_fines_applied(env.clone(), betData.clone().gameid);
         let (_, startTime, endTime, summiter, checkers, _) =
             storage::existBet(env.clone(), betData.clone().gameid);
+        let timeline: Timeline = storage::get_timeline(env.clone(), betData.clone().gameid);
+        let params: Params = storage::get_game_params(env.clone(), betData.clone().gameid);
 
         let doneBefore = storage::get_didUserWithdraw(env.clone(), user.clone(), setting.clone());
         if doneBefore {
             panic_with_error!(&env, BettingError::UserAlreadyClaimed);
         }
-        if startTime < env.ledger().timestamp() as u32 {
-            if betData.betType == BetType::Private {
-                let privateBet: PrivateBet =
-                    storage::get_PrivateBet(env.clone(), betData.clone().Setting);
-                if privateBet.clone().id == 0 {
-                    panic_with_error!(&env, BettingError::SettingBetDoesNotExist);
-                }
-                if privateBet.clone().active {
-                    if endTime + (3 * ONE_HOUR_SECONDS) < env.ledger().timestamp() as u32 {
-                        if receivedResult.id != 0 {
-                            panic_with_error!(&env, BettingError::GameIsActive);
-                        }
-                    } else {
+        // the bettors of a game cancelled by the admin get their stakes back right away
+        let cancelled = storage::get_gameCancelled(env.clone(), betData.clone().gameid);
+        if startTime < env.ledger().timestamp() as u32 || cancelled {
+            let (_, _, settingActive) = Self::setting_status(env.clone(), betData.clone().Setting);
+            if settingActive {
+                if endTime + timeline.admin_window < env.ledger().timestamp() as u32 {
+                    if receivedResult.id != 0 {
                         panic_with_error!(&env, BettingError::GameIsActive);
                     }
+                } else {
+                    panic_with_error!(&env, BettingError::GameIsActive);
                 }
-            } else {
-                panic_with_error!(&env, BettingError::InvalidInputError);
             }
-            if endTime + (3 * ONE_HOUR_SECONDS) < env.ledger().timestamp() as u32 {
+            if !cancelled && endTime + timeline.admin_window < env.ledger().timestamp() as u32 {
                 if receivedResult.id == 0 {
                     //check if the fines have been applied if not, we do it
                     if finesApplied != 2 {
                         let mut summiters: Vec<Address> = checkers.clone();
                         summiters.push_front(summiter.clone());
                         for user in summiters.iter() {
-                            storage::set_history(env.clone(), user.clone(), LESS_HUNDRED_POINTS);
-                            let stake: i128 = storage::get_stakeAmount_user_game(
+                            storage::set_history(
+                                env.clone(),
+                                user.clone(),
+                                -params.summiter_points,
+                            );
+                            let stake: i128 = Self::slash_stake(
                                 env.clone(),
                                 user.clone(),
                                 betData.clone().gameid,
@@ -219,9 +323,9 @@ impl betting for BettingContract {
                     }
                     let totalFine = storage::get_Fine(env.clone(), betData.clone().gameid);
                     totalBet += storage::get_total_bet(env.clone(), betData.clone().gameid);
-                    let user_share = (betData.clone().amount_bet * 100) / totalBet;
+                    let user_share = (amountBet * 100) / totalBet;
                     let user_amount = (user_share * totalFine) / 100;
-                    amountUsd = user_amount + betData.clone().amount_bet
+                    amountUsd = user_amount + amountBet
                 }
             }
             Self::moveToken(&env, &usd, &contract_address, &user, &amountUsd);
@@ -230,9 +334,9 @@ impl betting for BettingContract {
                 &trust,
                 &contract_address,
                 &user,
-                &((betData.clone().amount_bet * TRUST_TOKEN_PERCENTAGE) / 100),
+                &((amountBet * params.trust_percentage) / 100),
             );
-            storage::add_HonestyPoints(env.clone(), user.clone(), TWENTY_POINTS);
+            storage::add_HonestyPoints(env.clone(), user.clone(), params.bet_points);
             let points = storage::get_HonestyPoints(env.clone(), user.clone());
             BettingEvents::user_honesty_points(&env, user.clone(), points);
             storage::set_didUserWithdraw(env.clone(), user.clone(), setting.clone());
@@ -245,41 +349,345 @@ impl betting for BettingContract {
        @dev This function set a game to be bet on with the admin premission
        @param env Environment
        @param game Game The game data
-       @param signature BytesN<64> The signature of the game data
-       @param pub_key BytesN<32> The public key of the signer
+       @param key_id u32 The publisher key that signed the game, ADMIN_SIGNER for the admin key
+       @param envelope SignedEnvelope The contract, network, nonce and expiry the signature is bound to
+       @param signature BytesN<64> The signature of the envelope and the game data
     */
-    fn set_game(env: Env, game: Game, signature: BytesN<64>) -> bool {
-        let (exist, startTime, endTime, summiter, checkers, _) =
-            storage::existBet(env.clone(), game.clone().id);
-        if exist {
-            panic_with_error!(&env, BettingError::GameHasAlreadySet);
+    fn set_game(
+        env: Env,
+        game: Game,
+        key_id: u32,
+        envelope: SignedEnvelope,
+        signature: BytesN<64>,
+    ) -> bool {
+        Self::check_new_game(env.clone(), game.clone());
+        let signer = Self::publisher_key(env.clone(), key_id, game.league);
+        Self::verify_signature(
+            env.clone(),
+            signer,
+            key_id,
+            envelope,
+            game.clone().to_xdr(&env),
+            signature,
+        );
+        Self::store_game(env.clone(), game, key_id);
+        true
+    }
+    /*
+       @dev This function publish the Merkle root of a fixture list signed by a publisher,
+       the games of the list can then be set by anyone with a proof
+       @param env Environment
+       @param root BytesN<32> The Merkle root of the fixture list
+       @param key_id u32 The publisher key that signed the root, ADMIN_SIGNER for the admin key
+       @param envelope SignedEnvelope The contract, network, nonce and expiry the signature is bound to
+       @param signature BytesN<64> The signature of the envelope and the root
+    */
+    fn publish_fixtures(
+        env: Env,
+        root: BytesN<32>,
+        key_id: u32,
+        envelope: SignedEnvelope,
+        signature: BytesN<64>,
+    ) -> bool {
+        if storage::get_fixtureRoot(env.clone(), root.clone()).is_some() {
+            panic_with_error!(&env, BettingError::InvalidInputError);
         }
-        if game.clone().id == 0
-            || game.clone().startTime == 0
-            || game.clone().endTime == 0
-            || game.clone().startTime >= game.clone().endTime
-            || game.active
+        let signer = if key_id == ADMIN_SIGNER {
+            storage::get_admin_pubkey(env.clone())
+        } else {
+            match storage::get_publisher(env.clone(), key_id) {
+                Some(publisher) if !publisher.revoked => publisher.pubkey,
+                _ => panic_with_error!(&env, BettingError::UnknownSigner),
+            }
+        };
+        Self::verify_signature(
+            env.clone(),
+            signer,
+            key_id,
+            envelope,
+            root.clone().to_xdr(&env),
+            signature,
+        );
+        storage::set_fixtureRoot(env.clone(), root.clone(), key_id);
+        BettingEvents::fixtures_published(&env, root, key_id);
+        true
+    }
+    /*
+       @dev This function set a game of a published fixture list, anyone can call it
+       The publisher of the root must still be allowed to sign games of the league of the game
+       @param env Environment
+       @param root BytesN<32> The Merkle root of the fixture list
+       @param game Game The game data
+       @param proof Vec<BytesN<32>> The sibling hashes from the leaf of the game up to the root
+    */
+    fn set_proven_game(env: Env, root: BytesN<32>, game: Game, proof: Vec<BytesN<32>>) -> bool {
+        Self::set_fixture_game(env.clone(), root, game, proof);
+        true
+    }
+    /*
+       @dev This function set several games of a published fixture list in one call, anyone can call it
+       @param env Environment
+       @param root BytesN<32> The Merkle root of the fixture list
+       @param games Vec<Game> The games to set
+       @param proofs Vec<Vec<BytesN<32>>> The proof of each game, in the same order
+    */
+    fn set_games(
+        env: Env,
+        root: BytesN<32>,
+        games: Vec<Game>,
+        proofs: Vec<Vec<BytesN<32>>>,
+    ) -> u32 {
+        if games.is_empty() || games.len() != proofs.len() {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        for (game, proof) in games.iter().zip(proofs.iter()) {
+            Self::set_fixture_game(env.clone(), root.clone(), game, proof);
+        }
+        games.len()
+    }
+    /*
+       @dev This function commit the admin to a secret that seeds the summiter draw of a game
+       The draw waits for the secret to be revealed once the game is activated, so the activating bet can't choose the summiters.
+       The admin bonds SEED_COMMIT_BOND, given back when the secret is revealed and forfeited to the game if it is withheld
+       @param env Environment
+       @param user Address The address of the admin
+       @param game_id i128 The id of the game
+       @param commitment BytesN<32> The sha256 hash of the secret
+    */
+    fn commit_seed(env: Env, user: Address, game_id: i128, commitment: BytesN<32>) -> bool {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        let (exist, _, _, _, _, active) = storage::existBet(env.clone(), game_id.clone());
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if storage::get_gameCancelled(env.clone(), game_id.clone()) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        let mut draw: SelectionDraw = storage::get_selectionDraw(env.clone(), game_id.clone());
+        let zero = BytesN::from_array(&env, &[0; 32]);
+        if active || draw.commitment != zero || commitment == zero {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        let contract_address = env.current_contract_address();
+        let usd = storage::get_usd(env.clone());
+        Self::moveToken(&env, &usd, &user, &contract_address, &SEED_COMMIT_BOND);
+        draw.commitment = commitment.clone();
+        draw.bond = SEED_COMMIT_BOND;
+        storage::set_selectionDraw(env.clone(), game_id.clone(), draw);
+        BettingEvents::seed_committed(&env, game_id, commitment);
+        true
+    }
+    /*
+       @dev This function reveal the secret committed for a game and draw its summiters, anyone holding the secret can call it
+       The seed is the sha256 of the secret followed by the entropy taken when the game was activated,
+       the secret must be revealed before the reveal deadline of the draw and the bond goes back to the admin
+       @param env Environment
+       @param game_id i128 The id of the game
+       @param secret BytesN<32> The secret committed by the admin
+       @return The seed of the draw
+    */
+    fn reveal_seed(env: Env, game_id: i128, secret: BytesN<32>) -> BytesN<32> {
+        let (exist, startTime, _, _, _, _) = storage::existBet(env.clone(), game_id.clone());
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if storage::get_gameCancelled(env.clone(), game_id.clone()) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        if startTime < env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::GameHasAlreadyStarted);
+        }
+        let draw: SelectionDraw = storage::get_selectionDraw(env.clone(), game_id.clone());
+        let zero = BytesN::from_array(&env, &[0; 32]);
+        if draw.commitment == zero || draw.entropy == zero || draw.drawn {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        // past the deadline the draw falls back to the ledger seed
+        if draw.reveal_deadline < env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        let hash: BytesN<32> = env.crypto().sha256(&Bytes::from(secret.clone())).to_bytes();
+        if hash != draw.commitment {
+            panic_with_error!(&env, BettingError::SeedMismatch);
+        }
+        let mut data = Bytes::from(secret);
+        data.append(&Bytes::from(draw.entropy));
+        let seed: BytesN<32> = env.crypto().sha256(&data).to_bytes();
+        Self::settle_seed_bond(env.clone(), game_id, true);
+        Self::draw_summiters(env.clone(), game_id, seed.clone());
+        seed
+    }
+    /*
+       @dev This function draw the summiters of a game without the admin secret, anyone can call it
+       With no commitment it can be called from the ledger after the activation, with a commitment only once the reveal
+       deadline passed without the secret, the bond of the admin then goes to the fines of the game so withholding it isn't free.
+       The seed is the sha256 of the entropy taken when the game was activated followed by the activation ledger and the
+       reveal deadline, all fixed before the call, so the caller can't revert until it gets the summiters it wants:
+       calling again in the same state makes the same draw. The activating bet can't read the entropy and the pool
+       was taken at the activation, so nobody can steer the draw with the seed
+       @param env Environment
+       @param game_id i128 The id of the game
+       @return The seed of the draw
+    */
+    fn fallback_seed(env: Env, game_id: i128) -> BytesN<32> {
+        let (exist, _, endTime, _, _, _) = storage::existBet(env.clone(), game_id.clone());
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if storage::get_gameCancelled(env.clone(), game_id.clone()) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        if endTime < env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::Game_HasFinished);
+        }
+        let draw: SelectionDraw = storage::get_selectionDraw(env.clone(), game_id.clone());
+        let zero = BytesN::from_array(&env, &[0; 32]);
+        if draw.entropy == zero
+            || draw.drawn
+            || draw.activated_ledger >= env.ledger().sequence()
+            || (draw.commitment != zero && draw.reveal_deadline >= env.ledger().timestamp() as u32)
         {
             panic_with_error!(&env, BettingError::InvalidInputError);
         }
-        let encoded = game.clone().to_xdr(&env);
-        // Now wrap into Soroban Bytes
-        let admin_pubkey = storage::get_admin_pubkey(env.clone());
+        if draw.commitment != zero {
+            Self::settle_seed_bond(env.clone(), game_id, false);
+        }
+        let mut data = Bytes::from(draw.entropy);
+        data.extend_from_array(&draw.activated_ledger.to_be_bytes());
+        data.extend_from_array(&draw.reveal_deadline.to_be_bytes());
+        let seed: BytesN<32> = env.crypto().sha256(&data).to_bytes();
+        Self::draw_summiters(env.clone(), game_id, seed.clone());
+        seed
+    }
+    /*
+       @dev This function cancel a game before it starts with the admin premission
+       The settings of the game are deactivated so every bettor can claim a refund right away,
+       and the stakes of the selected summiters go back to the leaderboard
+       @param env Environment
+       @param cancellation GameCancellation The game to cancel and the reason
+       @param envelope SignedEnvelope The contract, network, nonce and expiry the signature is bound to
+       @param signature BytesN<64> The signature of the envelope and the cancellation data
+    */
+    fn cancel_game(
+        env: Env,
+        cancellation: GameCancellation,
+        envelope: SignedEnvelope,
+        signature: BytesN<64>,
+    ) -> bool {
+        let (exist, startTime, _, _, _, _) =
+            storage::existBet(env.clone(), cancellation.clone().game_id);
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if storage::get_gameCancelled(env.clone(), cancellation.clone().game_id) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        if startTime < env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::GameHasAlreadyStarted);
+        }
+        Self::verify_signature(
+            env.clone(),
+            storage::get_admin_pubkey(env.clone()),
+            ADMIN_SIGNER,
+            envelope,
+            cancellation.clone().to_xdr(&env),
+            signature,
+        );
 
-        env.crypto()
-            .ed25519_verify(&admin_pubkey, &encoded, &signature);
-        storage::set_game(env.clone(), game.clone());
-        BettingEvents::game_set(&env, game.id);
+        storage::set_gameCancelled(env.clone(), cancellation.clone().game_id);
+        for setting in Self::game_settings(env.clone(), cancellation.clone().game_id).iter() {
+            Self::set_setting_active(env.clone(), setting.clone(), false);
+        }
+        Self::release_summiters(env.clone(), cancellation.clone().game_id);
+        // the bond of a secret that isn't overdue goes back to the admin
+        let draw: SelectionDraw =
+            storage::get_selectionDraw(env.clone(), cancellation.clone().game_id);
+        let overdue = draw.entropy != BytesN::from_array(&env, &[0; 32])
+            && draw.reveal_deadline < env.ledger().timestamp() as u32;
+        Self::settle_seed_bond(env.clone(), cancellation.clone().game_id, !overdue);
+        BettingEvents::game_cancelled(&env, cancellation.game_id, cancellation.reason);
+        true
+    }
+    /*
+       @dev This function move the start and end times of a game that hasn't finished with the admin premission
+       The positions placed before the reschedule can be cancelled without fee for RESCHEDULE_OPT_OUT_WINDOW,
+       and never after the new start time since bets can't be cancelled once the game started
+       @param env Environment
+       @param schedule GameSchedule The new times of the game
+       @param envelope SignedEnvelope The contract, network, nonce and expiry the signature is bound to
+       @param signature BytesN<64> The signature of the schedule data
+    */
+    fn reschedule_game(
+        env: Env,
+        schedule: GameSchedule,
+        envelope: SignedEnvelope,
+        signature: BytesN<64>,
+    ) -> bool {
+        let (exist, _, endTime, _, _, active) =
+            storage::existBet(env.clone(), schedule.clone().game_id);
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if storage::get_gameCancelled(env.clone(), schedule.clone().game_id) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        if endTime < env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::Game_HasFinished);
+        }
+        if storage::get_ResultGame(env.clone(), schedule.clone().game_id).id != 0 {
+            panic_with_error!(&env, BettingError::GameResultAlreadySet);
+        }
+        // a schedule can only be used once, in order
+        if schedule.version
+            != storage::get_gameReschedules(env.clone(), schedule.clone().game_id) + 1
+            || schedule.startTime < env.ledger().timestamp() as u32
+            || schedule.startTime >= schedule.endTime
+        {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        Self::verify_signature(
+            env.clone(),
+            storage::get_admin_pubkey(env.clone()),
+            ADMIN_SIGNER,
+            envelope,
+            schedule.clone().to_xdr(&env),
+            signature,
+        );
+
+        storage::reschedule_game(
+            env.clone(),
+            schedule.clone().game_id,
+            schedule.clone().startTime,
+            schedule.clone().endTime,
+        );
+        // the summiters of the old date may not be available for the new one
+        let reselected = schedule.reselect && active;
+        if reselected {
+            Self::release_summiters(env.clone(), schedule.clone().game_id);
+            Self::select_summiter(env.clone(), schedule.clone().game_id);
+        }
+        BettingEvents::game_rescheduled(
+            &env,
+            schedule.game_id,
+            schedule.startTime,
+            schedule.endTime,
+            reselected,
+        );
         true
     }
     /*
        @dev This function set a private bet setting for a game
        @param env Environment
        @param user Address The address of the user
-       @param privateData PrivateBet The private bet data
+       @param privateData PrivateBet The private bet data, its id is assigned by the contract
        @param game_id i128 The id of the game
+       @return i128 The id of the setting
     */
-    fn set_private_bet(env: Env, user: Address, privateData: PrivateBet, game_id: i128) -> bool {
+    fn set_private_bet(env: Env, user: Address, privateData: PrivateBet, game_id: i128) -> i128 {
         user.require_auth();
 
         let (exist, startTime, endTime, summiter, checkers, _) =
@@ -287,15 +695,29 @@ impl betting for BettingContract {
         if !exist {
             panic_with_error!(&env, BettingError::GameDoesNotExist);
         }
-        if (privateData.id == 0
-            || privateData.gameid != game_id.clone()
+        if storage::get_gameCancelled(env.clone(), game_id.clone()) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        if (privateData.gameid != game_id.clone()
             || privateData.amount_bet_min <= 0
+            || privateData.amount_bet_max < 0
+            || (privateData.amount_bet_max != 0
+                && privateData.amount_bet_max < privateData.amount_bet_min)
+            || (privateData.fixed_stake
+                && privateData.amount_bet_max != 0
+                && privateData.amount_bet_max != privateData.amount_bet_min)
             || privateData.users_invated.len() == 0
             || privateData.active
             || privateData.settingAdmin != user.clone())
         {
             panic_with_error!(&env, BettingError::InvalidInputError);
         }
+        Self::validate_market(env.clone(), privateData.clone().market);
+        let privateData = PrivateBet {
+            id: storage::next_id(env.clone(), IdKind::Setting),
+            ..privateData
+        };
+        storage::set_idOwner(env.clone(), IdKind::Setting, privateData.id, user.clone());
         storage::set_privateSetting(env.clone(), privateData.clone());
         storage::add_privateSettingList(env.clone(), game_id.clone(), privateData.id);
         BettingEvents::private_setting(
@@ -305,7 +727,45 @@ impl betting for BettingContract {
             user,
             privateData.amount_bet_min,
         );
-        true
+        privateData.id
+    }
+    /*
+       @dev This function set an extra public bet setting for a game with the admin premission
+       @param env Environment
+       @param user Address The address of the admin
+       @param publicData PublicBet The public bet data, its id is assigned by the contract
+       @param game_id i128 The id of the game
+       @return i128 The id of the setting
+    */
+    fn set_public_bet(env: Env, user: Address, publicData: PublicBet, game_id: i128) -> i128 {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        let (exist, startTime, _, _, _, _) = storage::existBet(env.clone(), game_id.clone());
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if storage::get_gameCancelled(env.clone(), game_id.clone()) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        if startTime < env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::GameHasAlreadyStarted);
+        }
+        if publicData.gameid != game_id.clone() || publicData.active {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        Self::validate_market(env.clone(), publicData.clone().market);
+        let publicData = PublicBet {
+            id: storage::next_id(env.clone(), IdKind::Setting),
+            ..publicData
+        };
+        storage::set_idOwner(env.clone(), IdKind::Setting, publicData.id, user.clone());
+        storage::set_publicSetting(env.clone(), publicData.clone());
+        storage::add_publicSettingList(env.clone(), game_id.clone(), publicData.id);
+        BettingEvents::public_setting(&env, game_id, publicData.id);
+        publicData.id
     }
     /*
     @dev This function add a user to a private bet setting
@@ -345,7 +805,7 @@ impl betting for BettingContract {
     */
     fn summitResult(env: Env, user: Address, result: ResultGame) -> bool {
         user.require_auth();
-        if result.clone().id == 0 || result.clone().gameid == 0 {
+        if result.clone().gameid == 0 {
             panic_with_error!(&env, BettingError::InvalidInputError);
         }
         let (exist, startTime, endTime, summiter, checkers, active) =
@@ -353,6 +813,8 @@ impl betting for BettingContract {
         let receivedResult: ResultGame =
             storage::get_ResultGame(env.clone(), result.clone().gameid);
         let finesApplied = storage::get_fines_applied(env.clone(), result.clone().gameid);
+        let timeline: Timeline = storage::get_timeline(env.clone(), result.clone().gameid);
+        let params: Params = storage::get_game_params(env.clone(), result.clone().gameid);
         let admin = storage::get_admin(env.clone());
         if receivedResult.id != 0 {
             panic_with_error!(&env, BettingError::GameResultAlreadySet);
@@ -360,27 +822,25 @@ impl betting for BettingContract {
         if !exist {
             panic_with_error!(&env, BettingError::GameDoesNotExist);
         }
+        if storage::get_gameCancelled(env.clone(), result.clone().gameid) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
 
         if endTime > env.ledger().timestamp() as u32 {
             panic_with_error!(&env, BettingError::GameHasNotFinished);
         }
-        if result.clone().gameid == 0 || result.clone().id == 0 {
-            panic_with_error!(&env, BettingError::InvalidInputError);
-        }
         if result.clone().distribution_executed || result.clone().pause {
             panic_with_error!(&env, BettingError::InvalidInputError);
         }
-        if endTime + (1 * ONE_HOUR_SECONDS) < env.ledger().timestamp() as u32 {
+        Self::validate_score(env.clone(), result.clone());
+        if endTime + timeline.summit_window < env.ledger().timestamp() as u32 {
             //we check if the first summiter is the admin if not we apply fine to the summiter and replace it with the admin
             // then checkers have the next hour to summit the result or they will be fined
             if summiter != admin {
                 if finesApplied == 0 {
-                    storage::set_history(env.clone(), summiter.clone(), -100);
-                    let stake: i128 = storage::get_stakeAmount_user_game(
-                        env.clone(),
-                        summiter.clone(),
-                        result.clone().gameid,
-                    );
+                    storage::set_history(env.clone(), summiter.clone(), -params.summiter_points);
+                    let stake: i128 =
+                        Self::slash_stake(env.clone(), summiter.clone(), result.clone().gameid);
                     storage::add_Fine(env.clone(), result.clone().gameid, stake);
                     storage::update_game(
                         env.clone(),
@@ -391,17 +851,14 @@ impl betting for BettingContract {
                     storage::set_fines_applied(env.clone(), result.clone().gameid, 1);
                 }
             }
-            if endTime + (2 * ONE_HOUR_SECONDS) < env.ledger().timestamp() as u32 {
+            if endTime + timeline.checker_window < env.ledger().timestamp() as u32 {
                 // 2 hours with no summition then the admin can set the result to be assessed by the users
                 // and checkers will be fined
                 if finesApplied == 1 {
                     for checker in checkers.iter() {
-                        storage::set_history(env.clone(), checker.clone(), -100);
-                        let stake: i128 = storage::get_stakeAmount_user_game(
-                            env.clone(),
-                            checker.clone(),
-                            result.clone().gameid,
-                        );
+                        storage::set_history(env.clone(), checker.clone(), -params.summiter_points);
+                        let stake: i128 =
+                            Self::slash_stake(env.clone(), checker.clone(), result.clone().gameid);
                         storage::add_Fine(env.clone(), result.clone().gameid, stake);
                     }
                     storage::update_game(
@@ -412,33 +869,25 @@ impl betting for BettingContract {
                     );
                     storage::set_fines_applied(env.clone(), result.clone().gameid, 2);
                 }
-                if endTime + (3 * ONE_HOUR_SECONDS) > env.ledger().timestamp() as u32 {
+                if endTime + timeline.admin_window > env.ledger().timestamp() as u32 {
                     if summiter != user {
                         panic_with_error!(&env, BettingError::NotAllowToSummitResult);
                     }
 
-                    storage::set_ResultGame(env.clone(), result.clone());
-                    BettingEvents::game_result(
-                        &env,
-                        result.gameid,
-                        result.result,
-                        result.description,
-                    );
+                    Self::store_result(env.clone(), user.clone(), result.clone());
                 }
             } else {
                 if !checkers.contains(&user) {
                     panic_with_error!(&env, BettingError::NotAllowToSummitResult);
                 }
-                storage::set_ResultGame(env.clone(), result.clone());
-                BettingEvents::game_result(&env, result.gameid, result.result, result.description);
+                Self::store_result(env.clone(), user.clone(), result.clone());
             }
         } else {
             if summiter != user {
                 panic_with_error!(&env, BettingError::NotAllowToSummitResult);
             }
 
-            storage::set_ResultGame(env.clone(), result.clone());
-            BettingEvents::game_result(&env, result.gameid, result.result, result.description);
+            Self::store_result(env.clone(), user.clone(), result.clone());
         }
         true
     }
@@ -466,7 +915,8 @@ impl betting for BettingContract {
         if endTime > env.ledger().timestamp() as u32 {
             panic_with_error!(&env, BettingError::GameHasNotFinished);
         }
-        if endTime + (5 * ONE_HOUR_SECONDS) < env.ledger().timestamp() as u32 {
+        let timeline: Timeline = storage::get_timeline(env.clone(), game_id.clone());
+        if endTime + timeline.assessment_window < env.ledger().timestamp() as u32 {
             panic_with_error!(&env, BettingError::GameAssesmentHasFinished);
         }
 
@@ -478,8 +928,12 @@ impl betting for BettingContract {
             storage::get_ResultAssessment(env.clone(), game_id.clone());
 
         if !checkers.contains(&user) {
-            let betResult: Bet = storage::get_Bet(env.clone(), user.clone(), setting.clone());
-            if betResult.id == 0 {
+            let (_, settingGame, _) = Self::setting_status(env.clone(), setting.clone());
+            if settingGame != game_id {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
+            let bets: Vec<Bet> = storage::get_Bets(env.clone(), user.clone(), setting.clone());
+            if bets.is_empty() {
                 panic_with_error!(&env, BettingError::BetNotFound);
             }
             if resultAssessment.UsersApprove.contains(&user)
@@ -503,33 +957,48 @@ impl betting for BettingContract {
                     results.pause = true;
                 }
             }
-            storage::add_UsersAmountVoted(env.clone(), setting.clone());
-            if storage::UsersAmount(env.clone(), setting.clone()) {
-                BettingEvents::all_vote(&env, setting.clone());
-            }
             storage::set_ResultAssessment(env.clone(), game_id.clone(), resultAssessment.clone());
-            storage::delete_not_assesed_yet(
-                env.clone(),
-                betResult.clone().Setting,
-                betResult.clone().amount_bet,
-                betResult.clone().bet,
-            );
             if results.pause {
                 BettingEvents::game_result_reject(&env, game_id.clone());
                 storage::puase_ResultGame(env.clone(), game_id.clone(), results.clone().pause);
-                storage::add_reject_total(
-                    env.clone(),
-                    betResult.clone().Setting,
-                    betResult.clone().amount_bet,
-                    betResult.clone().bet,
-                );
-            } else {
-                storage::add_approve_total(
-                    env.clone(),
-                    betResult.clone().Setting,
-                    betResult.clone().amount_bet,
-                    betResult.clone().bet,
-                );
+            }
+            // the vote is kept once per game, so it counts for every position the user holds
+            // in every setting of the game that hasn't been distributed
+            for gameSetting in Self::game_settings(env.clone(), game_id.clone()).iter() {
+                let settingBets: Vec<Bet> =
+                    storage::get_Bets(env.clone(), user.clone(), gameSetting.clone());
+                if settingBets.is_empty()
+                    || storage::get_settingDistributed(env.clone(), gameSetting.clone())
+                {
+                    continue;
+                }
+                storage::add_UsersAmountVoted(env.clone(), gameSetting.clone());
+                if storage::UsersAmount(env.clone(), gameSetting.clone()) {
+                    BettingEvents::all_vote(&env, gameSetting.clone());
+                }
+                for betResult in settingBets.iter() {
+                    storage::delete_not_assesed_yet(
+                        env.clone(),
+                        betResult.clone().Setting,
+                        betResult.clone().amount_bet,
+                        betResult.clone().bet,
+                    );
+                    if results.pause {
+                        storage::add_reject_total(
+                            env.clone(),
+                            betResult.clone().Setting,
+                            betResult.clone().amount_bet,
+                            betResult.clone().bet,
+                        );
+                    } else {
+                        storage::add_approve_total(
+                            env.clone(),
+                            betResult.clone().Setting,
+                            betResult.clone().amount_bet,
+                            betResult.clone().bet,
+                        );
+                    }
+                }
             }
         } else {
             if resultAssessment.CheckApprove.contains(&user)
@@ -587,75 +1056,53 @@ impl betting for BettingContract {
                 storage::zero_ClaimProtocol(env.clone());
             }
             ClaimType::User => {
-                let doneBefore =
-                    storage::get_didUserWithdraw(env.clone(), user.clone(), setting.clone());
-                if doneBefore {
+                let preview: ClaimPreview =
+                    Self::user_claim(env.clone(), user.clone(), setting.clone());
+                if preview.claimed {
                     panic_with_error!(&env, BettingError::UserAlreadyClaimed);
                 }
-                let (kindofUser, amountBet) =
-                    Self::what_kind_user(env.clone(), user.clone(), setting.clone());
-                let winner_pool = storage::get_winnerPool(env.clone(), setting.clone());
-                let loser_pool = storage::get_loserPool(env.clone(), setting.clone());
-                let amount_share = storage::get_pool_total(env.clone(), setting.clone());
-                match kindofUser {
-                    6 => {
-                        // NO BET
+                match preview.kind {
+                    UserKind::NoBet => {
                         panic_with_error!(&env, BettingError::NoBetHasBeenFound);
                     }
-                    5 => {
-                        // loser who didn't assess the result
-                        panic_with_error!(&env, BettingError::NothingToClaim);
-                    }
-                    4 => {
-                        // winner who didn't assess the result
-                        // user gets 50% of his bet back and no trust back
-                        let bet_50 = (amountBet * 50) / 100;
-                        Self::moveToken(&env, &usd, &contract_address, &user, &bet_50);
-
-                        storage::set_didUserWithdraw(env.clone(), user.clone(), setting.clone());
-                        amountWithdrew = (bet_50, 0);
-                    }
-                    3 => {
-                        // dishonest user
+                    UserKind::LoserNoVote | UserKind::Dishonest => {
                         // no money no trust back
                         panic_with_error!(&env, BettingError::NothingToClaim);
                     }
-                    2 => {
-                        // loser honest
-                        // user gets back trust tokens
-                        if winner_pool == 0 {
-                            let user_share = (amountBet * 100) / loser_pool;
-                            let user_amount = (user_share * amount_share) / 100;
-                            Self::moveToken(&env, &usd, &contract_address, &user, &user_amount);
-                            let trust_amount = (amountBet * TRUST_TOKEN_PERCENTAGE) / 100;
-                            Self::moveToken(&env, &trust, &contract_address, &user, &trust_amount);
-                            amountWithdrew = (user_amount, trust_amount);
-                        } else {
-                            let trust_amount = (amountBet * TRUST_TOKEN_PERCENTAGE) / 100;
-                            Self::moveToken(&env, &trust, &contract_address, &user, &trust_amount);
-                            amountWithdrew = (0, trust_amount);
+                    UserKind::WinnerNoVote | UserKind::LoserHonest | UserKind::WinnerHonest => {
+                        if preview.amountUsd != 0 {
+                            Self::moveToken(
+                                &env,
+                                &usd,
+                                &contract_address,
+                                &user,
+                                &preview.amountUsd,
+                            );
+                        }
+                        if preview.amountTrust != 0 {
+                            Self::moveToken(
+                                &env,
+                                &trust,
+                                &contract_address,
+                                &user,
+                                &preview.amountTrust,
+                            );
+                        }
+                        if preview.points != 0 {
+                            storage::add_HonestyPoints(env.clone(), user.clone(), preview.points);
+                            let points = storage::get_HonestyPoints(env.clone(), user.clone());
+                            BettingEvents::user_honesty_points(&env, user.clone(), points);
                         }
-                        storage::add_HonestyPoints(env.clone(), user.clone(), FIFTY_POINTS);
-                        let points = storage::get_HonestyPoints(env.clone(), user.clone());
-                        BettingEvents::user_honesty_points(&env, user.clone(), points);
-                        storage::set_didUserWithdraw(env.clone(), user.clone(), setting.clone());
-                    }
-                    1 => {
-                        // winner honest
-                        let user_share = (amountBet * 100) / winner_pool;
-                        let user_amount = (user_share * amount_share) / 100;
-                        let total = amountBet + user_amount;
-                        Self::moveToken(&env, &usd, &contract_address, &user, &total);
-                        let trust_amount = (amountBet * TRUST_TOKEN_PERCENTAGE) / 100;
-                        Self::moveToken(&env, &trust, &contract_address, &user, &trust_amount);
-                        storage::add_HonestyPoints(env.clone(), user.clone(), FIFTY_POINTS);
-                        let points = storage::get_HonestyPoints(env.clone(), user.clone());
-                        BettingEvents::user_honesty_points(&env, user.clone(), points);
                         storage::set_didUserWithdraw(env.clone(), user.clone(), setting.clone());
-                        amountWithdrew = (total, trust_amount);
-                    }
-                    _ => {
-                        panic_with_error!(&env, BettingError::InvalidInputError);
+                        BettingEvents::user_claim(
+                            &env,
+                            setting.clone(),
+                            user.clone(),
+                            preview.settlement,
+                            preview.amountUsd,
+                            preview.amountTrust,
+                        );
+                        amountWithdrew = (preview.amountUsd, preview.amountTrust);
                     }
                 }
             }
@@ -666,6 +1113,15 @@ impl betting for BettingContract {
         }
         amountWithdrew
     }
+    /*
+       @dev This function preview what a user would get when claiming a setting without moving tokens
+       @param env Environment
+       @param user Address The address of the user
+       @param setting i128 The id of the setting
+    */
+    fn preview_claim(env: Env, user: Address, setting: i128) -> ClaimPreview {
+        Self::user_claim(env.clone(), user.clone(), setting.clone())
+    }
     /*
        @dev This function set the result of the game when a complain has been made and the time to summit the result has passed
        @param env Environment
@@ -681,7 +1137,8 @@ impl betting for BettingContract {
         let xresult: ResultGame = storage::get_ResultGame(env.clone(), result.clone().gameid);
         let (exist, startTime, endTime, summiter, checkers, _) =
             storage::existBet(env.clone(), xresult.clone().gameid);
-        if endTime + (5 * ONE_HOUR_SECONDS) > env.ledger().timestamp() as u32 {
+        let timeline: Timeline = storage::get_timeline(env.clone(), xresult.clone().gameid);
+        if endTime + timeline.assessment_window > env.ledger().timestamp() as u32 {
             panic_with_error!(&env, BettingError::GameAssesmentHasFinished);
         }
         if xresult.clone().distribution_executed {
@@ -699,43 +1156,37 @@ impl betting for BettingContract {
         {
             panic_with_error!(&env, BettingError::InvalidInputError);
         }
-        if xresult.result != result.result {
+        Self::validate_score(env.clone(), result.clone());
+        if xresult.result != result.result
+            || xresult.score != result.score
+            || xresult.half_time != result.half_time
+            || xresult.outcomes != result.outcomes
+        {
             complain = 0; // The complain made by the users was correct
         } else {
             complain = 1; // The complain made by the users was incorrect
         }
-        let listedPrivateBet: Vec<(i128)> =
-            storage::get_privateSettingList(env.clone(), result.clone().gameid);
+        let listedSettings: Vec<(i128)> = Self::game_settings(env.clone(), result.clone().gameid);
 
         if result.result == BetKey::Cancel {
-            storage::active_public_setting(env.clone(), result.clone().gameid, false);
-
-            let mut summittersList: Vec<Address> = checkers.clone();
-            summittersList.push_front(summiter.clone());
-            for summiter in summittersList.iter() {
-                let stake = storage::get_stakeAmount_user_game(
-                    env.clone(),
-                    summiter.clone(),
-                    result.clone().gameid,
-                );
-                Self::adduser_board(&env, summiter.clone(), stake);
-            }
+            Self::release_summiters(env.clone(), result.clone().gameid);
         }
-        if listedPrivateBet.len() != 0 {
-            for setting in listedPrivateBet.iter() {
-                let privateBet: PrivateBet = storage::get_PrivateBet(env.clone(), setting.clone());
-                if privateBet.active == false {
+        if listedSettings.len() != 0 {
+            for setting in listedSettings.iter() {
+                let (_, settingGame, settingActive) =
+                    Self::setting_status(env.clone(), setting.clone());
+                if settingActive == false {
                     continue;
                 }
 
                 if result.result == BetKey::Cancel {
-                    storage::active_private_setting(env.clone(), setting.clone(), false);
+                    Self::set_setting_active(env.clone(), setting.clone(), false);
                 } else {
                     Self::make_distribution(
                         env.clone(),
-                        privateBet.clone().gameid,
+                        settingGame,
                         setting.clone(),
-                        result.clone().result,
+                        result.clone(),
                         complain,
                     );
                 }
@@ -756,15 +1207,17 @@ impl betting for BettingContract {
         let result: ResultGame = storage::get_ResultGame(env.clone(), gameId.clone());
         let (exist, startTime, endTime, summiter, checkers, _) =
             storage::existBet(env.clone(), result.clone().gameid);
-        if endTime + (5 * ONE_HOUR_SECONDS) > env.ledger().timestamp() as u32 {
+        let timeline: Timeline = storage::get_timeline(env.clone(), gameId.clone());
+        if endTime + timeline.assessment_window > env.ledger().timestamp() as u32 {
             if !storage::UsersAmount(env.clone(), setting.clone()) {
                 panic_with_error!(&env, BettingError::GameAssesmentHasFinished);
             }
         }
-        let listedPrivateBet: Vec<(i128)> =
-            storage::get_privateSettingList(env.clone(), result.clone().gameid);
-        let privateBet: PrivateBet = storage::get_PrivateBet(env.clone(), setting.clone());
-        if privateBet.active == false {
+        let (_, settingGame, settingActive) = Self::setting_status(env.clone(), setting.clone());
+        if settingGame != gameId {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        if settingActive == false {
             panic_with_error!(&env, BettingError::SettingNotActive);
         }
         if result.id == 0 {
@@ -773,31 +1226,22 @@ impl betting for BettingContract {
         if result.pause == true {
             panic_with_error!(&env, BettingError::GameHasBeenPaused);
         }
-        if result.clone().distribution_executed {
+        // a game can have several settings, each one is distributed on its own
+        if storage::get_settingDistributed(env.clone(), setting.clone()) {
             panic_with_error!(&env, BettingError::GameHasAlreadyBeenExecuted);
         }
         if result.result == BetKey::Cancel {
-            storage::active_public_setting(env.clone(), gameId, false);
-            let mut summittersList: Vec<Address> = checkers.clone();
-            summittersList.push_front(summiter.clone());
-            for summiter in summittersList.iter() {
-                let stake = storage::get_stakeAmount_user_game(
-                    env.clone(),
-                    summiter.clone(),
-                    result.clone().gameid,
-                );
-                Self::adduser_board(&env, summiter.clone(), stake);
-            }
+            Self::release_summiters(env.clone(), result.clone().gameid);
         }
 
         if result.result == BetKey::Cancel {
-            storage::active_private_setting(env.clone(), setting.clone(), false);
+            Self::set_setting_active(env.clone(), setting.clone(), false);
         } else {
             Self::make_distribution(
                 env.clone(),
-                privateBet.clone().gameid,
+                settingGame,
                 setting.clone(),
-                result.clone().result,
+                result.clone(),
                 complain,
             );
         }
@@ -822,415 +1266,1557 @@ impl betting for BettingContract {
         BettingEvents::set_stake_amount(&env, amount);
         true
     }
-}
-
-impl BettingContract {
     /*
-       @dev This  funtion make the distribution of the pools according to the rules, fines and betting
+       @dev This function set the stake locked from a summiter for every game he is selected for
        @param env Environment
-       @param game_id i128 The id of the game
-       @param setting i128 The id of the setting
-       @param resultBet BetKey The result of the game
-       @param complain i128 The complain made by the users
-       Complain 0 = The complain made by the users was correct
-       Complain 1 = The complain made by the users was incorrect
-       Complain 2 = No complain was made
+       @param user Address The address of the admin
+       @param amount i128 The amount locked per game
     */
-    fn make_distribution(
+    fn set_stake_lock(env: Env, user: Address, amount: i128) -> bool {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        if amount <= 0 {
+            panic_with_error!(&env, BettingError::NegativeAmountError);
+        }
+        storage::set_stake_lock(env.clone(), amount);
+        BettingEvents::set_stake_lock(&env, amount);
+        true
+    }
+    /*
+       @dev This function register a publisher key that can sign the games of some leagues
+       Registering an existing id replaces its key and leagues
+       @param env Environment
+       @param user Address The address of the admin
+       @param key_id u32 The id of the publisher
+       @param pubkey BytesN<32> The public key of the publisher
+       @param leagues Vec<i128> The leagues the publisher can sign games for
+    */
+    fn add_publisher(
         env: Env,
-        game_id: i128,
-        setting: i128,
-        resultBet: BetKey,
-        complain: i128,
-    ) {
-        let mut result: ResultGame = storage::get_ResultGame(env.clone(), game_id.clone());
-        let mut amount_gain_pool: i128 = 0;
-        let mut trust_taken_pool: i128 = 0;
-        let mut losers_honest_pool: i128 = 0;
-        let mut winner_pool: i128 = 0;
-        let mut novote_winner: i128 = 0;
-        let mut s_dishonest: Vec<Address> = Vec::new(&env);
-        let mut s_honest: Vec<Address> = Vec::new(&env);
-        let mut s_noVote: Vec<Address> = Vec::new(&env);
-        let mut add = 0;
-        let admin = storage::get_admin(env.clone());
-
-        let mut resultAssessment: ResultAssessment =
-            storage::get_ResultAssessment(env.clone(), game_id.clone());
-
-        let (exist, startTime, endTime, summiter, checkers, _) =
-            storage::existBet(env.clone(), game_id.clone());
-        if !exist {
-            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        user: Address,
+        key_id: u32,
+        pubkey: BytesN<32>,
+        leagues: Vec<i128>,
+    ) -> bool {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
         }
-        if endTime > env.ledger().timestamp() as u32 {
-            panic_with_error!(&env, BettingError::GameHasNotFinished);
+        if key_id == ADMIN_SIGNER || leagues.is_empty() {
+            panic_with_error!(&env, BettingError::InvalidInputError);
         }
-        for i in 0..=2 {
-            let mut bet_key: BetKey = BetKey::Team_local;
-            match i {
-                0 => {
-                    bet_key = BetKey::Team_local;
-                }
-                1 => {
-                    bet_key = BetKey::Draw;
-                }
-                2 => {
-                    bet_key = BetKey::Team_away;
-                }
-                _ => {}
-            }
-            let amountBet =
-                storage::get_not_assesed_yet(env.clone(), setting.clone(), bet_key.clone());
-            if resultBet != bet_key {
-                amount_gain_pool += amountBet;
-                trust_taken_pool += amountBet;
-            } else {
-                novote_winner += amountBet;
-                trust_taken_pool += amountBet;
-            }
+        storage::set_publisher(
+            env.clone(),
+            key_id,
+            Publisher {
+                pubkey: pubkey.clone(),
+                leagues: leagues.clone(),
+                revoked: false,
+            },
+        );
+        BettingEvents::publisher_added(&env, key_id, pubkey, leagues);
+        true
+    }
+    /*
+       @dev This function revoke a publisher key, the games it already set are kept
+       @param env Environment
+       @param user Address The address of the admin
+       @param key_id u32 The id of the publisher
+    */
+    fn revoke_publisher(env: Env, user: Address, key_id: u32) -> bool {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
         }
-        let winner_minus_50 = (novote_winner * 50) / 100;
-        amount_gain_pool += winner_minus_50;
-        match complain {
-            0 => {
-                // summiter
-                s_dishonest.push_back(summiter.clone());
-                add += storage::get_stakeAmount_user_game(
-                    env.clone(),
-                    summiter.clone(),
-                    game_id.clone(),
-                );
-                //Checkers
-                for checker in checkers.iter() {
-                    if resultAssessment.CheckApprove.contains(&checker) {
-                        s_dishonest.push_back(checker.clone());
-                        add += storage::get_stakeAmount_user_game(
-                            env.clone(),
-                            checker.clone(),
-                            game_id.clone(),
-                        );
-                        storage::set_history(env.clone(), checker.clone(), LESS_HUNDRED_POINTS);
-                    } else if resultAssessment.CheckReject.contains(&checker) {
-                        s_honest.push_back(checker.clone());
-                        storage::set_history(env.clone(), checker.clone(), HUNDRED_POINTS);
-                    } else {
-                        s_noVote.push_back(checker.clone());
-                        add += storage::get_stakeAmount_user_game(
-                            env.clone(),
-                            checker.clone(),
-                            game_id.clone(),
-                        );
-                        storage::set_history(env.clone(), checker.clone(), LESS_HUNDRED_POINTS);
-                    }
-                }
-                let dishonestTeamLocal =
-                    storage::get_approve_total(env.clone(), setting.clone(), BetKey::Team_local);
-                let dishonestDraw =
-                    storage::get_approve_total(env.clone(), setting.clone(), BetKey::Draw);
-                let dishonestTeamAway =
-                    storage::get_approve_total(env.clone(), setting.clone(), BetKey::Team_away);
-                amount_gain_pool += dishonestTeamAway;
-                amount_gain_pool += dishonestTeamLocal;
-                amount_gain_pool += dishonestDraw;
-                trust_taken_pool += dishonestTeamAway;
-                trust_taken_pool += dishonestTeamLocal;
-                trust_taken_pool += dishonestDraw;
-
-                for i in 0..=2 {
-                    let mut bet_key: BetKey = BetKey::Team_local;
-                    match i {
-                        0 => {
-                            bet_key = BetKey::Team_local;
-                        }
-                        1 => {
-                            bet_key = BetKey::Draw;
-                        }
-                        2 => {
-                            bet_key = BetKey::Team_away;
-                        }
-                        _ => {}
-                    }
-                    let amountBet =
-                        storage::get_reject_total(env.clone(), setting.clone(), bet_key.clone());
-                    if resultBet != bet_key {
-                        amount_gain_pool += amountBet;
-                        losers_honest_pool += amountBet;
-                    } else {
-                        winner_pool += amountBet;
-                    }
-                }
-            }
-            1 => {
-                // summiter
-                s_honest.push_back(summiter.clone());
-                //Checkers
-                for checker in checkers.iter() {
-                    if resultAssessment.CheckApprove.contains(&checker) {
-                        s_honest.push_back(checker.clone());
-                        storage::set_history(env.clone(), checker.clone(), HUNDRED_POINTS);
-                    } else if resultAssessment.CheckReject.contains(&checker) {
-                        s_dishonest.push_back(checker.clone());
-                        add += storage::get_stakeAmount_user_game(
-                            env.clone(),
-                            checker.clone(),
-                            game_id.clone(),
-                        );
-                        storage::set_history(env.clone(), checker.clone(), LESS_HUNDRED_POINTS);
-                    } else {
-                        s_noVote.push_back(checker.clone());
-                        add += storage::get_stakeAmount_user_game(
-                            env.clone(),
-                            checker.clone(),
-                            game_id.clone(),
-                        );
-                        storage::set_history(env.clone(), checker.clone(), LESS_HUNDRED_POINTS);
-                    }
-                }
-
-                let dishonestTeamLocal =
-                    storage::get_reject_total(env.clone(), setting.clone(), BetKey::Team_local);
-                let dishonestDraw =
-                    storage::get_reject_total(env.clone(), setting.clone(), BetKey::Draw);
-                let dishonestTeamAway =
-                    storage::get_reject_total(env.clone(), setting.clone(), BetKey::Team_away);
-                amount_gain_pool += dishonestTeamAway;
-                amount_gain_pool += dishonestTeamLocal;
-                amount_gain_pool += dishonestDraw;
-                trust_taken_pool += dishonestTeamAway;
-                trust_taken_pool += dishonestTeamLocal;
-                trust_taken_pool += dishonestDraw;
-                for i in 0..=2 {
-                    let mut bet_key: BetKey = BetKey::Team_local;
-                    match i {
-                        0 => {
-                            bet_key = BetKey::Team_local;
-                        }
-                        1 => {
-                            bet_key = BetKey::Draw;
-                        }
-                        2 => {
-                            bet_key = BetKey::Team_away;
-                        }
-                        _ => {}
-                    }
-                    let amountBet =
-                        storage::get_approve_total(env.clone(), setting.clone(), bet_key.clone());
-                    if resultBet != bet_key {
-                        amount_gain_pool += amountBet;
-                        losers_honest_pool += amountBet;
-                    } else {
-                        winner_pool += amountBet;
-                    }
-                }
+        let mut publisher = storage::get_publisher(env.clone(), key_id)
+            .unwrap_or_else(|| panic_with_error!(&env, BettingError::UnknownSigner));
+        publisher.revoked = true;
+        storage::set_publisher(env.clone(), key_id, publisher);
+        BettingEvents::publisher_revoked(&env, key_id);
+        true
+    }
+    /*
+       @dev This function set how the summiter and the checkers of the games are drawn
+       @param env Environment
+       @param user Address The address of the admin
+       @param policy SelectionPolicy The new policy
+    */
+    fn set_selection_policy(env: Env, user: Address, policy: SelectionPolicy) -> bool {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        if policy.pool_size == 0
+            || policy.pool_size > MAX_SELECTION_POOL
+            || policy.checkers > policy.max_checkers
+            || policy.max_checkers >= MAX_SELECTION_POOL
+            || policy.value_per_checker < 0
+        {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        storage::set_selection_policy(env.clone(), policy.clone());
+        BettingEvents::selection_policy(&env, policy);
+        true
+    }
+    /*
+       @dev This function set the deadlines of the games, by default or for a league or a game
+       @param env Environment
+       @param user Address The address of the admin
+       @param scope TimelineScope The games the timeline applies to
+       @param timeline Timeline The windows in seconds after the end of the game
+    */
+    fn set_timeline(env: Env, user: Address, scope: TimelineScope, timeline: Timeline) -> bool {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        if timeline.summit_window == 0
+            || timeline.checker_window <= timeline.summit_window
+            || timeline.admin_window <= timeline.checker_window
+            || timeline.assessment_window <= timeline.admin_window
+        {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        // the deadlines of a game can't change once it has finished
+        if let TimelineScope::Game(game_id) = scope.clone() {
+            let (exist, _, endTime, _, _, _) = storage::existBet(env.clone(), game_id);
+            if !exist {
+                panic_with_error!(&env, BettingError::GameDoesNotExist);
             }
-            2 => {
-                // summiter
-                s_honest.push_back(summiter.clone());
-                //Checkers
-                for checker in checkers.iter() {
-                    if resultAssessment.CheckApprove.contains(&checker) {
-                        s_honest.push_back(checker.clone());
-                        storage::set_history(env.clone(), checker.clone(), HUNDRED_POINTS);
-                    } else if resultAssessment.CheckReject.contains(&checker) {
-                        s_dishonest.push_back(checker.clone());
-                        add += storage::get_stakeAmount_user_game(
-                            env.clone(),
-                            checker.clone(),
-                            game_id.clone(),
-                        );
-                        storage::set_history(env.clone(), checker.clone(), LESS_HUNDRED_POINTS);
-                    } else {
-                        s_noVote.push_back(checker.clone());
-                        add += storage::get_stakeAmount_user_game(
-                            env.clone(),
-                            checker.clone(),
-                            game_id.clone(),
-                        );
-                        storage::set_history(env.clone(), checker.clone(), HUNDRED_POINTS);
-                    }
-                }
-                for i in 0..=2 {
-                    let mut bet_key: BetKey = BetKey::Team_local;
-                    match i {
-                        0 => {
-                            bet_key = BetKey::Team_local;
-                        }
-                        1 => {
-                            bet_key = BetKey::Draw;
-                        }
-                        2 => {
-                            bet_key = BetKey::Team_away;
-                        }
-                        _ => {}
-                    }
-                    let amountBet =
-                        storage::get_approve_total(env.clone(), setting.clone(), bet_key.clone());
-                    if resultBet != bet_key {
-                        amount_gain_pool += amountBet;
-                        losers_honest_pool += amountBet;
-                    } else {
-                        winner_pool += amountBet;
-                    }
-                }
+            if endTime < env.ledger().timestamp() as u32 {
+                panic_with_error!(&env, BettingError::Game_HasFinished);
             }
-            _ => {
+        }
+        storage::set_timeline(env.clone(), scope.clone(), timeline.clone());
+        BettingEvents::timeline_set(&env, scope, timeline);
+        true
+    }
+    /*
+       @dev This function propose new economic parameters, they can be applied once the timelock has passed
+       A new proposal replaces the one waiting
+       @param env Environment
+       @param user Address The address of the admin
+       @param params Params The new parameters
+       @return The time from which the parameters can be applied
+    */
+    fn propose_params(env: Env, user: Address, params: Params) -> u32 {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        let percentages = [
+            params.protocol_fee,
+            params.summiter_fee,
+            params.no_winner_summiter_fee,
+            params.trust_percentage,
+            params.no_vote_penalty,
+            params.cancel_fee,
+        ];
+        for percentage in percentages {
+            if percentage < 0 || percentage > 100 {
                 panic_with_error!(&env, BettingError::InvalidInputError);
             }
         }
-        amount_gain_pool += add;
-        amount_gain_pool += storage::get_Fine(env.clone(), game_id.clone());
-        storage::zero_Fine(env.clone(), game_id.clone());
-        let mut summiter_retribution = 0;
-        let mut protocol_retribution = (amount_gain_pool * TEN_PERCENT) / 100;
-        if winner_pool == 0 && losers_honest_pool == 0 {
-            if s_honest.len() != 0 {
-                if summiter == admin {
-                    protocol_retribution = amount_gain_pool;
-                } else {
-                    summiter_retribution = (amount_gain_pool * FIFTY_PERCENT) / 100;
-                    for honest in s_honest.iter() {
-                        let mut amount = summiter_retribution / s_honest.len() as i128;
-                        amount += storage::get_stakeAmount_user_game(
-                            env.clone(),
-                            honest.clone(),
-                            game_id.clone(),
-                        );
-                        storage::add_ClaimSummiter(env.clone(), honest.clone(), amount);
-                    }
-                    protocol_retribution = (amount_gain_pool * FIFTY_PERCENT) / 100;
-                }
-            }
-        } else if s_honest.len() != 0 {
-            if summiter != admin {
-                summiter_retribution = (amount_gain_pool * TWENTY_PERCENT) / 100;
-                for honest in s_honest.iter() {
-                    let mut amount = summiter_retribution / s_honest.len() as i128;
-                    amount += storage::get_stakeAmount_user_game(
-                        env.clone(),
-                        honest.clone(),
-                        game_id.clone(),
-                    );
-                    storage::add_ClaimSummiter(env.clone(), honest.clone(), amount);
-                }
+        for points in [
+            params.bet_points,
+            params.honest_points,
+            params.summiter_points,
+        ] {
+            if points < 0 || points > MAX_POINTS {
+                panic_with_error!(&env, BettingError::InvalidInputError);
             }
         }
-        amount_gain_pool -= protocol_retribution;
-        amount_gain_pool -= summiter_retribution;
-        let trust_amount = (trust_taken_pool * TRUST_TOKEN_PERCENTAGE) / 100;
-        storage::add_ClaimProtocol(env.clone(), protocol_retribution);
-        storage::add_ClaimProtocolTrust(env.clone(), trust_amount);
-        storage::save_complain(env.clone(), game_id.clone(), complain);
-        storage::save_winnerPool(env.clone(), setting.clone(), winner_pool);
-        storage::save_loserPool(env.clone(), setting.clone(), losers_honest_pool);
-        storage::set_pool_total(env.clone(), setting.clone(), amount_gain_pool);
-        storage::distribution_ResultGame(env.clone(), game_id.clone());
-        result.distribution_executed = true;
-        storage::set_ResultGame(env.clone(), result.clone());
-        BettingEvents::game_setting_distributed(&env, setting);
+        if params.protocol_fee + params.summiter_fee > 100 {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        let effective_at = env.ledger().timestamp() as u32 + PARAMS_TIMELOCK;
+        storage::set_params_proposal(
+            env.clone(),
+            ParamsProposal {
+                params: params.clone(),
+                effective_at,
+            },
+        );
+        BettingEvents::params_proposed(&env, params, effective_at);
+        effective_at
     }
-
     /*
-       @dev Function to determine the kind of user based on their bet and assessment
-       @param env The contract environment
-       @param user The address of the user
-       @param setting The setting ID of the bet
-       @return A tuple containing the kind of user (as an integer) and the amount bet (if applicable)
-       1: winner and honest
-       2: loser and honest
-       3: user dishonest
-       4: winner who didn't assess the result
-       5: loser who didn't assess the result
-       6: no bet
-    */
-    fn what_kind_user(env: Env, user: Address, setting: i128) -> (i32, i128) {
-        let betData: Bet = storage::get_bet(env.clone(), user.clone(), setting.clone());
-        if betData.id == 0 {
-            return (6, 0); // no bet
-        }
-        let (exist, startTime, endTime, summiter, checkers, _) =
-            storage::existBet(env.clone(), betData.clone().gameid);
-        if !exist {
-            panic_with_error!(&env, BettingError::GameDoesNotExist);
+       @dev This function apply the proposed parameters once the timelock has passed, anyone can call it
+       Only the games set from now on use them
+       @param env Environment
+    */
+    fn apply_params(env: Env) -> bool {
+        let proposal: ParamsProposal = match storage::get_params_proposal(env.clone()) {
+            Some(proposal) => proposal,
+            None => panic_with_error!(&env, BettingError::InvalidInputError),
+        };
+        if proposal.effective_at > env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::ParamsTimelocked);
         }
-        let xresult: ResultGame = storage::get_ResultGame(env.clone(), betData.clone().gameid);
-        let resultAssessment: ResultAssessment =
-            storage::get_ResultAssessment(env.clone(), betData.clone().gameid);
-        let complain = storage::get_complain(env.clone(), betData.clone().gameid);
+        storage::set_params(env.clone(), proposal.clone().params);
+        storage::remove_params_proposal(env.clone());
+        BettingEvents::params_applied(&env, proposal.params);
+        true
+    }
+    /*
+       @dev This function propose a new admin, the transfer is done when the new admin accepts it
+       Proposing again replaces the pending admin
+       @param env Environment
+       @param user Address The address of the admin
+       @param new_admin Address The address proposed as admin
+    */
+    fn propose_admin(env: Env, user: Address, new_admin: Address) -> bool {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        storage::set_admin_proposal(env.clone(), new_admin.clone());
+        BettingEvents::admin_proposed(&env, user, new_admin);
+        true
+    }
+    /*
+       @dev This function accept the admin role by the address proposed by the admin
+       @param env Environment
+       @param new_admin Address The address proposed as admin
+    */
+    fn accept_admin(env: Env, new_admin: Address) -> bool {
+        new_admin.require_auth();
+        let proposed: Address = match storage::get_admin_proposal(env.clone()) {
+            Some(proposed) => proposed,
+            None => panic_with_error!(&env, BettingError::InvalidInputError),
+        };
+        if proposed != new_admin {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        let previous: Address = storage::get_admin(env.clone());
+        storage::set_admin(env.clone(), new_admin.clone());
+        storage::remove_admin_proposal(env.clone());
+        BettingEvents::admin_transferred(&env, previous, new_admin);
+        true
+    }
+    /*
+       @dev This function propose a new public key to sign the games, cancellations and schedules
+       It can be applied by anyone once ROTATION_TIMELOCK has passed, proposing again restarts the timelock
+       @param env Environment
+       @param user Address The address of the admin
+       @param pubkey BytesN<32> The new public key
+       @return The timestamp from which the key can be applied
+    */
+    fn propose_pubkey(env: Env, user: Address, pubkey: BytesN<32>) -> u32 {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        let effective_at = env.ledger().timestamp() as u32 + ROTATION_TIMELOCK;
+        storage::set_pubkey_rotation(
+            env.clone(),
+            PubkeyRotation {
+                pubkey: pubkey.clone(),
+                effective_at,
+            },
+        );
+        BettingEvents::pubkey_proposed(&env, pubkey, effective_at);
+        effective_at
+    }
+    /*
+       @dev This function apply the proposed public key once its timelock has passed
+       @param env Environment
+    */
+    fn apply_pubkey(env: Env) -> bool {
+        let rotation: PubkeyRotation = match storage::get_pubkey_rotation(env.clone()) {
+            Some(rotation) => rotation,
+            None => panic_with_error!(&env, BettingError::InvalidInputError),
+        };
+        if rotation.effective_at > env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::RotationTimelocked);
+        }
+        let previous = storage::get_admin_pubkey(env.clone());
+        storage::set_admin_pubkey(env.clone(), rotation.clone().pubkey);
+        storage::remove_pubkey_rotation(env.clone());
+        BettingEvents::pubkey_rotated(&env, previous, rotation.pubkey);
+        true
+    }
+    /*
+       @dev This function propose a new supreme court
+       It can be applied by anyone once ROTATION_TIMELOCK has passed, proposing again restarts the timelock
+       @param env Environment
+       @param user Address The address of the admin
+       @param supreme_court Address The new supreme court
+       @return The timestamp from which the supreme court can be applied
+    */
+    fn propose_supreme_court(env: Env, user: Address, supreme_court: Address) -> u32 {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        let effective_at = env.ledger().timestamp() as u32 + ROTATION_TIMELOCK;
+        storage::set_court_rotation(
+            env.clone(),
+            CourtRotation {
+                supreme_court: supreme_court.clone(),
+                effective_at,
+            },
+        );
+        BettingEvents::court_proposed(&env, supreme_court, effective_at);
+        effective_at
+    }
+    /*
+       @dev This function apply the proposed supreme court once its timelock has passed
+       @param env Environment
+    */
+    fn apply_supreme_court(env: Env) -> bool {
+        let rotation: CourtRotation = match storage::get_court_rotation(env.clone()) {
+            Some(rotation) => rotation,
+            None => panic_with_error!(&env, BettingError::InvalidInputError),
+        };
+        if rotation.effective_at > env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::RotationTimelocked);
+        }
+        let previous = storage::get_supreme(env.clone());
+        storage::set_supreme(env.clone(), rotation.clone().supreme_court);
+        storage::remove_court_rotation(env.clone());
+        BettingEvents::court_rotated(&env, previous, rotation.supreme_court);
+        true
+    }
+    /*
+       @dev This function move the entries of the leaderboard of the previous versions to the pages, a batch at a time
+       Summiters already listed in the pages or unbonding are dropped
+       @param env Environment
+       @param user Address The address of the admin
+       @param limit u32 The number of entries moved in this call
+       @return The number of entries left to move
+    */
+    fn migrate_leaderboard(env: Env, user: Address, limit: u32) -> u32 {
+        user.require_auth();
+        let adminAdr: Address = storage::get_admin(env.clone());
+        if adminAdr != user {
+            panic_with_error!(&env, BettingError::NotAdmin);
+        }
+        let legacy: Vec<(Address, i128)> = storage::get_legacy_leaderboard(env.clone());
+        let moved = limit.min(legacy.len());
+        for (addr, score) in legacy.slice(0..moved).iter() {
+            let mut bond: SummiterBond = storage::get_summiterBond(env.clone(), addr.clone());
+            if bond.listed || bond.unbonding {
+                continue;
+            }
+            storage::board_insert(env.clone(), addr.clone(), score);
+            bond.listed = true;
+            bond.score = score;
+            storage::set_summiterBond(env.clone(), addr.clone(), bond);
+        }
+        let remaining = legacy.slice(moved..legacy.len());
+        storage::set_legacy_leaderboard(env.clone(), remaining.clone());
+        BettingEvents::leaderboard_migrated(&env, moved, remaining.len());
+        remaining.len()
+    }
+    /*
+       @dev This function start the exit of a summiter, the user leaves the leaderboard so it is not selected again
+       The stake can be withdrawn after a cooldown that also covers the assessment of every game the user is assigned to
+       @param env Environment
+       @param user Address The address of the summiter
+       @return The time from which the stake can be withdrawn
+    */
+    fn request_unbond(env: Env, user: Address) -> u32 {
+        user.require_auth();
+        let mut bond: SummiterBond = storage::get_summiterBond(env.clone(), user.clone());
+        if bond.unbonding {
+            panic_with_error!(&env, BettingError::SummiterUnbonding);
+        }
+        let stake = storage::get_stakeAmount_user(env.clone(), user.clone());
+        if stake == 0 && bond.games.len() == 0 {
+            panic_with_error!(&env, BettingError::NotEnoughStake);
+        }
+        let now = env.ledger().timestamp() as u32;
+        let mut available_at = now + UNBOND_COOLDOWN;
+        for game_id in bond.games.iter() {
+            let (_, _, endTime, _, _, _) = storage::existBet(env.clone(), game_id.clone());
+            let timeline: Timeline = storage::get_timeline(env.clone(), game_id.clone());
+            let deadline = endTime + timeline.assessment_window;
+            if deadline > available_at {
+                available_at = deadline;
+            }
+        }
+        if bond.listed {
+            storage::board_remove(env.clone(), user.clone(), bond.score);
+            bond.listed = false;
+        }
+        bond.unbonding = true;
+        bond.available_at = available_at;
+        storage::set_summiterBond(env.clone(), user.clone(), bond);
+        BettingEvents::unbond_requested(&env, user, stake, available_at);
+        available_at
+    }
+    /*
+       @dev This function send back the stake of a summiter once the unbonding cooldown has passed
+       and every game it was drawn for has released or slashed its locked stake
+       A game that is never settled (a paused result the supreme court doesn't rule on, a result never distributed)
+       holds its lock only until SUPREME_COURT_WINDOW after its assessment, the lock is released then
+       The fines of the assigned games are taken from the stake of the game so only the remaining stake is sent
+       @param env Environment
+       @param user Address The address of the summiter
+       @return The amount withdrawn
+    */
+    fn withdraw_stake(env: Env, user: Address) -> i128 {
+        user.require_auth();
+        let bond: SummiterBond = storage::get_summiterBond(env.clone(), user.clone());
+        if !bond.unbonding {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        if bond.available_at > env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::StakeLocked);
+        }
+        let now = env.ledger().timestamp() as u32;
+        for game_id in bond.games.iter() {
+            let (_, _, endTime, _, _, _) = storage::existBet(env.clone(), game_id.clone());
+            let timeline: Timeline = storage::get_timeline(env.clone(), game_id.clone());
+            if endTime + timeline.assessment_window + SUPREME_COURT_WINDOW > now {
+                panic_with_error!(&env, BettingError::GamesStillAssigned);
+            }
+        }
+        for game_id in bond.games.iter() {
+            Self::unlock_stake(env.clone(), user.clone(), game_id);
+        }
+        let amount = storage::get_stakeAmount_user(env.clone(), user.clone());
+        storage::set_stakeAmount_user(env.clone(), user.clone(), 0);
+        storage::remove_summiterBond(env.clone(), user.clone());
+        if amount > 0 {
+            let usd = storage::get_usd(env.clone());
+            let contract_address = env.current_contract_address();
+            Self::moveToken(&env, &usd, &contract_address, &user, &amount);
+        }
+        BettingEvents::stake_withdrawn(&env, user, amount);
+        amount
+    }
+    /*
+       @dev This function place a parlay, a ticket that combines outcomes of public settings of different games
+       The stake is locked in the contract until every leg is settled, and the winnings it can pay at MAX_PARLAY_ODDS
+       are reserved from the parlay pool, so a ticket the pool can't back is rejected
+       @param env Environment
+       @param user Address The address of the user
+       @param parlay Parlay The parlay data, its id is assigned by the contract and returned
+    */
+    fn bet_parlay(env: Env, user: Address, parlay: Parlay) -> i128 {
+        user.require_auth();
+        let contract_address = env.current_contract_address();
+        let usd = storage::get_usd(env.clone());
+        if parlay.amount_bet <= 0
+            || parlay.claimed
+            || parlay.legs.len() < 2
+            || parlay.legs.len() > MAX_PARLAY_LEGS
+        {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        let mut games: Vec<i128> = Vec::new(&env);
+        for leg in parlay.legs.iter() {
+            let publicBet: PublicBet = storage::get_PublicBet(env.clone(), leg.setting);
+            if publicBet.id == 0 {
+                panic_with_error!(&env, BettingError::PublicSettingNotFound);
+            }
+            // legs of the same game are not independent
+            if games.contains(&publicBet.gameid) {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
+            games.push_back(publicBet.gameid);
+            let (exist, startTime, _, _, _, _) = storage::existBet(env.clone(), publicBet.gameid);
+            if !exist {
+                panic_with_error!(&env, BettingError::GameDoesNotExist);
+            }
+            if storage::get_gameCancelled(env.clone(), publicBet.gameid) {
+                panic_with_error!(&env, BettingError::GameCancelled);
+            }
+            if startTime < env.ledger().timestamp() as u32 {
+                panic_with_error!(&env, BettingError::GameHasAlreadyStarted);
+            }
+            if storage::CheckUser(env.clone(), user.clone(), publicBet.gameid) {
+                panic_with_error!(&env, BettingError::Summiters_notAllowToBet);
+            }
+            if leg.outcome >= Self::market_outcomes(publicBet.market) {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
+        }
+        let reserve = Self::parlay_reserve(parlay.amount_bet);
+        let reserved = storage::get_ParlayReserved(env.clone()) + reserve;
+        if reserved > storage::get_ParlayPool(env.clone()) {
+            panic_with_error!(&env, BettingError::BalanceError);
+        }
+        storage::set_ParlayReserved(env.clone(), reserved);
+        // the id is assigned by the contract whatever the caller sends
+        let mut parlay = parlay;
+        parlay.id = storage::next_id(env.clone(), IdKind::Parlay);
+        storage::set_idOwner(env.clone(), IdKind::Parlay, parlay.id, user.clone());
+        storage::set_Parlay(env.clone(), user.clone(), parlay.clone());
+        Self::moveToken(&env, &usd, &user, &contract_address, &parlay.amount_bet);
+        BettingEvents::parlay_bet(&env, user, parlay.id, parlay.amount_bet);
+        parlay.id
+    }
+    /*
+       @dev This function settle a parlay once every leg is settled, anyone can call it and the payout goes to the owner
+       The winnings are paid from the reserve of the parlay and are capped at MAX_PARLAY_ODDS, lost stakes go to the pool
+       @param env Environment
+       @param user Address The owner of the parlay
+       @param parlay i128 The id of the parlay
+    */
+    fn claim_parlay(env: Env, user: Address, parlay: i128) -> i128 {
+        let contract_address = env.current_contract_address();
+        let usd = storage::get_usd(env.clone());
+        let mut parlayData: Parlay = storage::get_Parlay(env.clone(), user.clone(), parlay);
+        if parlayData.id == 0 {
+            panic_with_error!(&env, BettingError::BetNotFound);
+        }
+        if parlayData.claimed {
+            panic_with_error!(&env, BettingError::UserAlreadyClaimed);
+        }
+        let mut payout = parlayData.amount_bet;
+        for leg in parlayData.legs.iter() {
+            payout = (payout * Self::leg_odds(env.clone(), leg)) / EVEN_ODDS;
+        }
+        let reserve = Self::parlay_reserve(parlayData.amount_bet);
+        let mut pool = storage::get_ParlayPool(env.clone());
+        if payout > parlayData.amount_bet {
+            let winnings = (payout - parlayData.amount_bet).min(reserve);
+            payout = parlayData.amount_bet + winnings;
+            pool -= winnings;
+        } else {
+            pool += parlayData.amount_bet - payout;
+        }
+        storage::set_ParlayPool(env.clone(), pool);
+        storage::set_ParlayReserved(
+            env.clone(),
+            storage::get_ParlayReserved(env.clone()) - reserve,
+        );
+        parlayData.claimed = true;
+        storage::set_Parlay(env.clone(), user.clone(), parlayData.clone());
+        if payout != 0 {
+            Self::moveToken(&env, &usd, &contract_address, &user, &payout);
+        }
+        BettingEvents::parlay_claim(&env, user, parlay, payout);
+        BettingEvents::parlay_pool(&env, pool);
+        payout
+    }
+    /*
+       @dev This function add funds to the pool that pays the parlays
+       @param env Environment
+       @param user Address The address funding the pool
+       @param amount i128 The amount to add
+    */
+    fn fund_parlay_pool(env: Env, user: Address, amount: i128) -> bool {
+        user.require_auth();
+        if amount <= 0 {
+            panic_with_error!(&env, BettingError::NegativeAmountError);
+        }
+        let contract_address = env.current_contract_address();
+        let usd = storage::get_usd(env.clone());
+        Self::moveToken(&env, &usd, &user, &contract_address, &amount);
+        let pool = storage::get_ParlayPool(env.clone()) + amount;
+        storage::set_ParlayPool(env.clone(), pool);
+        BettingEvents::parlay_pool(&env, pool);
+        true
+    }
+    /*
+       @dev This function withdraw the position of a user on an outcome before the game starts
+       A fee of the stake goes to the protocol unless the position is in the opt out window of a reschedule, the trust tokens are given back in full
+       @param env Environment
+       @param user Address The address of the user
+       @param setting i128 The id of the setting
+       @param outcome u32 The outcome of the position
+    */
+    fn cancel_bet(env: Env, user: Address, setting: i128, outcome: u32) -> i128 {
+        user.require_auth();
+        let contract_address = env.current_contract_address();
+        let usd = storage::get_usd(env.clone());
+        let trust: Address = storage::get_trust(env.clone());
+        let (_, gameId, _) = Self::setting_status(env.clone(), setting.clone());
+        let (exist, startTime, _, _, _, _) = storage::existBet(env.clone(), gameId.clone());
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if storage::get_gameCancelled(env.clone(), gameId.clone()) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        if startTime < env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::GameHasAlreadyStarted);
+        }
+        let removed: Bet =
+            storage::remove_bet(env.clone(), user.clone(), setting.clone(), outcome.clone());
+        if removed.id == 0 {
+            panic_with_error!(&env, BettingError::BetNotFound);
+        }
+        storage::delete_not_assesed_yet(
+            env.clone(),
+            setting.clone(),
+            removed.clone().amount_bet,
+            outcome.clone(),
+        );
+        storage::sub_total_bet(env.clone(), gameId.clone(), removed.clone().amount_bet);
+        // the user leaves the setting with his last position
+        let params: Params = storage::get_game_params(env.clone(), gameId.clone());
+        if storage::get_Bets(env.clone(), user.clone(), setting.clone()).is_empty() {
+            storage::sub_UsersAmount(env.clone(), setting.clone());
+            storage::add_HonestyPoints(env.clone(), user.clone(), params.bet_points);
+            let points = storage::get_HonestyPoints(env.clone(), user.clone());
+            BettingEvents::user_honesty_points(&env, user.clone(), points);
+        }
+        Self::refresh_setting_activity(env.clone(), setting.clone());
+        // the bettors that placed their position before the last reschedule can opt out without fee for a while
+        let mut fee = (removed.clone().amount_bet * params.cancel_fee) / 100;
+        if Self::in_opt_out_window(env.clone(), gameId.clone(), removed.clone().id) {
+            fee = 0;
+        }
+        let refund = removed.clone().amount_bet - fee;
+        storage::add_ClaimProtocol(env.clone(), fee);
+        Self::moveToken(&env, &usd, &contract_address, &user, &refund);
+        Self::moveToken(
+            &env,
+            &trust,
+            &contract_address,
+            &user,
+            &((removed.clone().amount_bet * params.trust_percentage) / 100),
+        );
+        BettingEvents::bet_cancel(&env, user, setting, outcome, refund, fee);
+        refund
+    }
+    /*
+       @dev This function move the position of a user to another outcome before the game starts
+       The whole stake is moved without fee, it tops up the position on the new outcome if there is one,
+       unless one of the two positions can still be cancelled without fee after a reschedule
+       @param env Environment
+       @param user Address The address of the user
+       @param setting i128 The id of the setting
+       @param outcome u32 The outcome of the position
+       @param newOutcome u32 The new pick of the user
+    */
+    fn update_bet(env: Env, user: Address, setting: i128, outcome: u32, newOutcome: u32) -> bool {
+        user.require_auth();
+        let (betType, gameId, _) = Self::setting_status(env.clone(), setting.clone());
+        let (exist, startTime, _, _, _, _) = storage::existBet(env.clone(), gameId.clone());
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if storage::get_gameCancelled(env.clone(), gameId.clone()) {
+            panic_with_error!(&env, BettingError::GameCancelled);
+        }
+        if startTime < env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::GameHasAlreadyStarted);
+        }
+        let market: MarketType = Self::setting_market(env.clone(), setting.clone());
+        if newOutcome == outcome || newOutcome >= Self::market_outcomes(market) {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        if betType == BetType::Private {
+            let privateBet: PrivateBet = storage::get_PrivateBet(env.clone(), setting.clone());
+            // a fixed stake can't be topped up
+            if privateBet.fixed_stake {
+                for position in storage::get_Bets(env.clone(), user.clone(), setting.clone()).iter()
+                {
+                    if position.bet == newOutcome {
+                        panic_with_error!(&env, BettingError::PrivateBet_NotEnoughToBet);
+                    }
+                }
+            }
+        }
+        let mut moved: Bet =
+            storage::remove_bet(env.clone(), user.clone(), setting.clone(), outcome.clone());
+        if moved.id == 0 {
+            panic_with_error!(&env, BettingError::BetNotFound);
+        }
+        // merged positions share one placement time, so neither can be inside its opt out window
+        for position in storage::get_Bets(env.clone(), user.clone(), setting.clone()).iter() {
+            if position.bet == newOutcome
+                && (Self::in_opt_out_window(env.clone(), gameId.clone(), moved.clone().id)
+                    || Self::in_opt_out_window(env.clone(), gameId.clone(), position.id))
+            {
+                panic_with_error!(&env, BettingError::PositionInOptOutWindow);
+            }
+        }
+        storage::delete_not_assesed_yet(
+            env.clone(),
+            setting.clone(),
+            moved.clone().amount_bet,
+            outcome.clone(),
+        );
+        moved.bet = newOutcome;
+        storage::add_bet(env.clone(), user.clone(), moved.clone());
+        storage::add_not_assesed_yet(
+            env.clone(),
+            setting.clone(),
+            moved.clone().amount_bet,
+            newOutcome.clone(),
+        );
+        Self::refresh_setting_activity(env.clone(), setting.clone());
+        BettingEvents::bet_update(&env, user, setting, outcome, newOutcome);
+        true
+    }
+    /*
+       @dev This function return the data of a game
+       @param env Environment
+       @param game_id i128 The id of the game
+    */
+    fn get_game(env: Env, game_id: i128) -> Game {
+        let game: Game = storage::get_game(env.clone(), game_id.clone());
+        if game.id == 0 {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        game
+    }
+    /*
+       @dev This function return whether the admin cancelled a game
+       @param env Environment
+       @param game_id i128 The id of the game
+    */
+    fn is_game_cancelled(env: Env, game_id: i128) -> bool {
+        storage::get_gameCancelled(env.clone(), game_id.clone())
+    }
+    /*
+       @dev This function return the free stake of a summiter, the part that is not locked for any game
+       @param env Environment
+       @param user Address The address of the summiter
+    */
+    fn get_stake(env: Env, user: Address) -> i128 {
+        storage::get_stakeAmount_user(env.clone(), user.clone())
+    }
+    /*
+       @dev This function return the stake locked from a summiter for every game he is selected for
+       @param env Environment
+    */
+    fn get_stake_lock(env: Env) -> i128 {
+        storage::get_stake_lock(env.clone())
+    }
+    /*
+       @dev This function return the address that owns an id assigned by the contract
+       @param env Environment
+       @param kind IdKind The kind of the id
+       @param id i128 The id
+    */
+    fn get_id_owner(env: Env, kind: IdKind, id: i128) -> Option<Address> {
+        storage::get_idOwner(env.clone(), kind, id)
+    }
+    /*
+       @dev This function return a registered publisher key
+       @param env Environment
+       @param key_id u32 The id of the publisher
+    */
+    fn get_publisher(env: Env, key_id: u32) -> Publisher {
+        storage::get_publisher(env.clone(), key_id)
+            .unwrap_or_else(|| panic_with_error!(&env, BettingError::UnknownSigner))
+    }
+    /*
+       @dev This function return how the summiter and the checkers of the games are drawn
+       @param env Environment
+    */
+    fn get_selection_policy(env: Env) -> SelectionPolicy {
+        storage::get_selection_policy(env.clone())
+    }
+    /*
+       @dev This function return the commitment, the entropy and the seed of the summiter draw of a game
+       The entropy is hidden until the draw is made, so the activating transaction can't work out the seed
+       @param env Environment
+       @param game_id i128 The id of the game
+    */
+    fn get_selection_draw(env: Env, game_id: i128) -> SelectionDraw {
+        let mut draw: SelectionDraw = storage::get_selectionDraw(env.clone(), game_id.clone());
+        if !draw.drawn {
+            draw.entropy = BytesN::from_array(&env, &[0; 32]);
+        }
+        draw
+    }
+    /*
+       @dev This function return the unbonding state of a summiter and the games its stake is assigned to
+       @param env Environment
+       @param user Address The address of the summiter
+    */
+    fn get_summiter_bond(env: Env, user: Address) -> SummiterBond {
+        storage::get_summiterBond(env.clone(), user.clone())
+    }
+    /*
+       @dev This function return the deadlines that apply to a game
+       @param env Environment
+       @param game_id i128 The id of the game
+    */
+    fn get_timeline(env: Env, game_id: i128) -> Timeline {
+        storage::get_timeline(env.clone(), game_id.clone())
+    }
+    /*
+       @dev This function return the economic parameters used by the new games
+       @param env Environment
+    */
+    fn get_params(env: Env) -> Params {
+        storage::get_params(env.clone())
+    }
+    /*
+       @dev This function return the admin
+       @param env Environment
+    */
+    fn get_admin(env: Env) -> Address {
+        storage::get_admin(env.clone())
+    }
+    /*
+       @dev This function return the address proposed as admin, if any
+       @param env Environment
+    */
+    fn get_pending_admin(env: Env) -> Option<Address> {
+        storage::get_admin_proposal(env.clone())
+    }
+    /*
+       @dev This function return the public key that signs the games
+       @param env Environment
+    */
+    fn get_admin_pubkey(env: Env) -> BytesN<32> {
+        storage::get_admin_pubkey(env.clone())
+    }
+    /*
+       @dev This function return the public key waiting for the timelock, effective_at is 0 when there is none
+       @param env Environment
+    */
+    fn get_pubkey_rotation(env: Env) -> PubkeyRotation {
+        storage::get_pubkey_rotation(env.clone()).unwrap_or(PubkeyRotation {
+            pubkey: storage::get_admin_pubkey(env.clone()),
+            effective_at: 0,
+        })
+    }
+    /*
+       @dev This function return the supreme court
+       @param env Environment
+    */
+    fn get_supreme_court(env: Env) -> Address {
+        storage::get_supreme(env.clone())
+    }
+    /*
+       @dev This function return the supreme court waiting for the timelock, effective_at is 0 when there is none
+       @param env Environment
+    */
+    fn get_court_rotation(env: Env) -> CourtRotation {
+        storage::get_court_rotation(env.clone()).unwrap_or(CourtRotation {
+            supreme_court: storage::get_supreme(env.clone()),
+            effective_at: 0,
+        })
+    }
+    /*
+       @dev This function return the parameters waiting for the timelock, effective_at is 0 when there is none
+       @param env Environment
+    */
+    fn get_params_proposal(env: Env) -> ParamsProposal {
+        storage::get_params_proposal(env.clone()).unwrap_or(ParamsProposal {
+            params: storage::get_params(env.clone()),
+            effective_at: 0,
+        })
+    }
+    /*
+       @dev This function return the parameters a game settles with
+       @param env Environment
+       @param game_id i128 The id of the game
+    */
+    fn get_game_params(env: Env, game_id: i128) -> Params {
+        storage::get_game_params(env.clone(), game_id.clone())
+    }
+    /*
+       @dev This function return the result summited for a game
+       @param env Environment
+       @param game_id i128 The id of the game
+    */
+    fn get_result(env: Env, game_id: i128) -> ResultGame {
+        let result: ResultGame = storage::get_ResultGame(env.clone(), game_id.clone());
+        if result.id == 0 {
+            panic_with_error!(&env, BettingError::GameResultNotFound);
+        }
+        result
+    }
+    /*
+       @dev This function return the votes of users and checkers on the result of a game
+       @param env Environment
+       @param game_id i128 The id of the game
+    */
+    fn get_assessment(env: Env, game_id: i128) -> ResultAssessment {
+        storage::get_ResultAssessment(env.clone(), game_id.clone())
+    }
+    /*
+       @dev This function return the ids of all the settings (public and private) of a game
+       @param env Environment
+       @param game_id i128 The id of the game
+    */
+    fn get_game_settings(env: Env, game_id: i128) -> Vec<i128> {
+        Self::game_settings(env.clone(), game_id.clone())
+    }
+    /*
+       @dev This function return a private setting
+       @param env Environment
+       @param setting i128 The id of the setting
+    */
+    fn get_setting(env: Env, setting: i128) -> PrivateBet {
+        let privateBet: PrivateBet = storage::get_PrivateBet(env.clone(), setting.clone());
+        if privateBet.id == 0 {
+            panic_with_error!(&env, BettingError::SettingBetDoesNotExist);
+        }
+        privateBet
+    }
+    /*
+       @dev This function return a public setting
+       @param env Environment
+       @param setting i128 The id of the setting
+    */
+    fn get_public_setting(env: Env, setting: i128) -> PublicBet {
+        let publicBet: PublicBet = storage::get_PublicBet(env.clone(), setting.clone());
+        if publicBet.id == 0 {
+            panic_with_error!(&env, BettingError::PublicSettingNotFound);
+        }
+        publicBet
+    }
+    /*
+       @dev This function return the positions of a user in a setting, one per outcome
+       @param env Environment
+       @param user Address The address of the user
+       @param setting i128 The id of the setting
+    */
+    fn get_bet(env: Env, user: Address, setting: i128) -> Vec<Bet> {
+        let bets: Vec<Bet> = storage::get_Bets(env.clone(), user.clone(), setting.clone());
+        if bets.is_empty() {
+            panic_with_error!(&env, BettingError::BetNotFound);
+        }
+        bets
+    }
+    /*
+       @dev This function return the honesty points of a user
+       @param env Environment
+       @param user Address The address of the user
+    */
+    fn get_honesty_points(env: Env, user: Address) -> i128 {
+        storage::get_HonestyPoints(env.clone(), user.clone())
+    }
+    /*
+       @dev This function return a page of the leaderboard ordered by score
+       @param env Environment
+       @param start u32 The position of the first entry
+       @param limit u32 The max number of entries
+    */
+    fn get_leaderboard(env: Env, start: u32, limit: u32) -> Vec<(Address, i128)> {
+        storage::get_leaderboard_range(env.clone(), start, limit)
+    }
+    /*
+       @dev This function return the pages of the leaderboard with their lowest score and size
+       @param env Environment
+    */
+    fn get_leaderboard_index(env: Env) -> LeaderboardIndex {
+        storage::get_leaderboard_index(env.clone())
+    }
+    /*
+       @dev This function return the pools of a setting computed at the distribution
+       @param env Environment
+       @param setting i128 The id of the setting
+    */
+    fn get_pools(env: Env, setting: i128) -> SettingPools {
+        SettingPools {
+            setting: setting.clone(),
+            distributed: storage::get_settingDistributed(env.clone(), setting.clone()),
+            winnerPool: storage::get_winnerPool(env.clone(), setting.clone()),
+            loserPool: storage::get_loserPool(env.clone(), setting.clone()),
+            pool: storage::get_pool_total(env.clone(), setting.clone()),
+        }
+    }
+    /*
+       @dev This function return the money a summiter or checker can claim
+       @param env Environment
+       @param user Address The address of the summiter
+    */
+    fn get_claimable(env: Env, user: Address) -> i128 {
+        storage::get_ClaimSummiter(env.clone(), user.clone())
+    }
+    /*
+       @dev This function return a parlay of a user
+       @param env Environment
+       @param user Address The owner of the parlay
+       @param parlay i128 The id of the parlay
+    */
+    fn get_parlay(env: Env, user: Address, parlay: i128) -> Parlay {
+        let parlayData: Parlay = storage::get_Parlay(env.clone(), user, parlay);
+        if parlayData.id == 0 {
+            panic_with_error!(&env, BettingError::BetNotFound);
+        }
+        parlayData
+    }
+    /*
+       @dev This function return the funds of the parlay pool
+       @param env Environment
+    */
+    fn get_parlay_pool(env: Env) -> i128 {
+        storage::get_ParlayPool(env.clone())
+    }
+    /*
+       @dev This function return the part of the parlay pool reserved for the open parlays
+       @param env Environment
+    */
+    fn get_parlay_reserved(env: Env) -> i128 {
+        storage::get_ParlayReserved(env.clone())
+    }
+}
+
+impl BettingContract {
+    /*
+       @dev Function to store the first result summited for a game with the id assigned to it
+       @param env The contract environment
+       @param user The address that summited the result, the owner of its id
+       @param result The result of the game
+    */
+    fn store_result(env: Env, user: Address, result: ResultGame) {
+        let id = storage::next_id(env.clone(), IdKind::Result);
+        storage::set_idOwner(env.clone(), IdKind::Result, id, user);
+        storage::set_ResultGame(
+            env.clone(),
+            ResultGame {
+                id,
+                ..result.clone()
+            },
+        );
+        BettingEvents::game_result(&env, result.gameid, result.result, result.description);
+    }
+    /*
+       @dev Function to check a game can be set: it is new, inactive and its times are valid
+       @param env The contract environment
+       @param game The game data
+    */
+    fn check_new_game(env: Env, game: Game) {
+        let (exist, _, _, _, _, _) = storage::existBet(env.clone(), game.clone().id);
+        if exist {
+            panic_with_error!(&env, BettingError::GameHasAlreadySet);
+        }
+        if game.clone().id == 0
+            || game.clone().id >= FIRST_SETTING_ID
+            || game.clone().startTime == 0
+            || game.clone().endTime == 0
+            || game.clone().startTime >= game.clone().endTime
+            || game.active
+        {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+    }
+    /*
+       @dev Function to store a game once its publisher is verified, with its parameters and its public setting
+       @param env The contract environment
+       @param game The game data
+       @param key_id The publisher key that signed the game
+    */
+    fn store_game(env: Env, game: Game, key_id: u32) {
+        storage::set_game(env.clone(), game.clone());
+        // the game settles with the parameters of today even if they change later
+        storage::set_game_params(
+            env.clone(),
+            game.clone().id,
+            storage::get_params(env.clone()),
+        );
+        // and within the deadlines of today
+        storage::set_timeline(
+            env.clone(),
+            TimelineScope::Game(game.clone().id),
+            storage::resolve_timeline(env.clone(), game.clone().league),
+        );
+        BettingEvents::game_set(&env, game.id);
+        BettingEvents::game_publisher(&env, game.id, key_id);
+        // every game gets an open setting that anyone can join, it uses the game id
+        let publicBet = PublicBet {
+            id: game.clone().id,
+            gameid: game.clone().id,
+            active: false,
+            description: game.clone().description,
+            market: MarketType::Winner,
+        };
+        storage::set_publicSetting(env.clone(), publicBet.clone());
+        storage::add_publicSettingList(env.clone(), game.clone().id, publicBet.id);
+        BettingEvents::public_setting(&env, game.id, publicBet.id);
+    }
+    /*
+       @dev Function to set a game of a published fixture list with its Merkle proof
+       The leaf of a game is the sha256 of its XDR and each node is the sha256 of its two children in ascending order
+       @param env The contract environment
+       @param root The Merkle root of the fixture list
+       @param game The game data
+       @param proof The sibling hashes from the leaf up to the root
+    */
+    fn set_fixture_game(env: Env, root: BytesN<32>, game: Game, proof: Vec<BytesN<32>>) {
+        let key_id = match storage::get_fixtureRoot(env.clone(), root.clone()) {
+            Some(key_id) => key_id,
+            None => panic_with_error!(&env, BettingError::UnknownSigner),
+        };
+        Self::check_new_game(env.clone(), game.clone());
+        Self::publisher_key(env.clone(), key_id, game.league);
+        let mut node: BytesN<32> = env.crypto().sha256(&game.clone().to_xdr(&env)).to_bytes();
+        for sibling in proof.iter() {
+            let mut data = Bytes::new(&env);
+            if node < sibling {
+                data.append(&Bytes::from(node));
+                data.append(&Bytes::from(sibling));
+            } else {
+                data.append(&Bytes::from(sibling));
+                data.append(&Bytes::from(node));
+            }
+            node = env.crypto().sha256(&data).to_bytes();
+        }
+        if node != root {
+            panic_with_error!(&env, BettingError::InvalidProof);
+        }
+        Self::store_game(env.clone(), game, key_id);
+    }
+    /*
+       @dev Function to get the key of a publisher allowed to sign the games of a league
+       @param env The contract environment
+       @param key_id The id of the publisher, ADMIN_SIGNER for the admin key
+       @param league The league of the game
+    */
+    fn publisher_key(env: Env, key_id: u32, league: i128) -> BytesN<32> {
+        if key_id == ADMIN_SIGNER {
+            return storage::get_admin_pubkey(env.clone());
+        }
+        match storage::get_publisher(env.clone(), key_id) {
+            Some(publisher) if !publisher.revoked && publisher.leagues.contains(league) => {
+                publisher.pubkey
+            }
+            _ => panic_with_error!(&env, BettingError::UnknownSigner),
+        }
+    }
+    /*
+       @dev Function to check a signature over an envelope and its payload
+       The envelope ties the signature to this contract, this network and a schema version,
+       it expires after its ledger and its nonce is spent for the signer so the signature can not be replayed
+       @param env The contract environment
+       @param signer The public key expected to sign
+       @param key_id The id of the signer, ADMIN_SIGNER for the admin key
+       @param envelope The domain of the signature
+       @param payload The XDR of the signed data
+       @param signature The signature of the envelope followed by the payload
+    */
+    fn verify_signature(
+        env: Env,
+        signer: BytesN<32>,
+        key_id: u32,
+        envelope: SignedEnvelope,
+        payload: Bytes,
+        signature: BytesN<64>,
+    ) {
+        if envelope.version != SIGNATURE_VERSION
+            || envelope.contract != env.current_contract_address()
+            || envelope.network_id != env.ledger().network_id()
+        {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        if envelope.expiry_ledger < env.ledger().sequence() {
+            panic_with_error!(&env, BettingError::SignatureExpired);
+        }
+        if storage::get_signatureNonce(env.clone(), key_id, envelope.nonce) {
+            panic_with_error!(&env, BettingError::SignatureReused);
+        }
+        let mut message = envelope.clone().to_xdr(&env);
+        message.append(&payload);
+        env.crypto().ed25519_verify(&signer, &message, &signature);
+        storage::set_signatureNonce(env.clone(), key_id, envelope.nonce);
+    }
+    /*
+       @dev This  funtion make the distribution of the pools according to the rules, fines and betting
+       @param env Environment
+       @param game_id i128 The id of the game
+       @param setting i128 The id of the setting
+       @param resultGame ResultGame The result of the game
+       @param complain i128 The complain made by the users
+       Complain 0 = The complain made by the users was correct
+       Complain 1 = The complain made by the users was incorrect
+       Complain 2 = No complain was made
+    */
+    fn make_distribution(
+        env: Env,
+        game_id: i128,
+        setting: i128,
+        resultGame: ResultGame,
+        complain: i128,
+    ) {
+        let mut result: ResultGame = storage::get_ResultGame(env.clone(), game_id.clone());
+        // each setting settles its own market from the result of the game
+        let market: MarketType = Self::setting_market(env.clone(), setting.clone());
+        let outcomes: u32 = Self::market_outcomes(market.clone());
+        let mut amount_gain_pool: i128 = 0;
+        let mut trust_taken_pool: i128 = 0;
+        let mut losers_honest_pool: i128 = 0;
+        let mut winner_pool: i128 = 0;
+        let mut novote_winner: i128 = 0;
+        let mut s_dishonest: Vec<Address> = Vec::new(&env);
+        let mut s_honest: Vec<Address> = Vec::new(&env);
+        let mut s_noVote: Vec<Address> = Vec::new(&env);
+        let mut add = 0;
+        let mut fined: Vec<Address> = Vec::new(&env);
+        let mut history: Vec<(Address, i128)> = Vec::new(&env);
+        let admin = storage::get_admin(env.clone());
+        let params: Params = storage::get_game_params(env.clone(), game_id.clone());
+
+        let mut resultAssessment: ResultAssessment =
+            storage::get_ResultAssessment(env.clone(), game_id.clone());
+
+        let (exist, startTime, endTime, summiter, checkers, _) =
+            storage::existBet(env.clone(), game_id.clone());
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        if endTime > env.ledger().timestamp() as u32 {
+            panic_with_error!(&env, BettingError::GameHasNotFinished);
+        }
+        for outcome in 0..outcomes {
+            let amountBet = storage::get_not_assesed_yet(env.clone(), setting.clone(), outcome);
+            let (win, push, loss) = Self::settled_stakes(
+                amountBet,
+                Self::outcome_settlement(
+                    market.clone(),
+                    setting.clone(),
+                    outcome,
+                    resultGame.clone(),
+                ),
+            );
+            // refunded stakes of users who didn't vote are fined like the winning ones
+            amount_gain_pool += loss;
+            novote_winner += win + push;
+            trust_taken_pool += amountBet;
+        }
+        amount_gain_pool += (novote_winner * params.no_vote_penalty) / 100;
         match complain {
             0 => {
-                if resultAssessment.UsersApprove.contains(&user) {
-                    return (3, 0); // user dishonest
+                // summiter
+                s_dishonest.push_back(summiter.clone());
+                fined.push_back(summiter.clone());
+                //Checkers
+                for checker in checkers.iter() {
+                    if resultAssessment.CheckApprove.contains(&checker) {
+                        s_dishonest.push_back(checker.clone());
+                        fined.push_back(checker.clone());
+                        history.push_back((checker.clone(), -params.summiter_points));
+                    } else if resultAssessment.CheckReject.contains(&checker) {
+                        s_honest.push_back(checker.clone());
+                        history.push_back((checker.clone(), params.summiter_points));
+                    } else {
+                        s_noVote.push_back(checker.clone());
+                        fined.push_back(checker.clone());
+                        history.push_back((checker.clone(), -params.summiter_points));
+                    }
+                }
+                for outcome in 0..outcomes {
+                    let dishonest =
+                        storage::get_approve_total(env.clone(), setting.clone(), outcome);
+                    amount_gain_pool += dishonest;
+                    trust_taken_pool += dishonest;
+                }
+
+                for outcome in 0..outcomes {
+                    let amountBet =
+                        storage::get_reject_total(env.clone(), setting.clone(), outcome);
+                    let (win, _, loss) = Self::settled_stakes(
+                        amountBet,
+                        Self::outcome_settlement(
+                            market.clone(),
+                            setting.clone(),
+                            outcome,
+                            resultGame.clone(),
+                        ),
+                    );
+                    amount_gain_pool += loss;
+                    losers_honest_pool += loss;
+                    winner_pool += win;
                 }
-                if resultAssessment.UsersReject.contains(&user) {
-                    if betData.bet == xresult.result {
-                        return (1, betData.clone().amount_bet); // winner and honest
+            }
+            1 => {
+                // summiter
+                s_honest.push_back(summiter.clone());
+                //Checkers
+                for checker in checkers.iter() {
+                    if resultAssessment.CheckApprove.contains(&checker) {
+                        s_honest.push_back(checker.clone());
+                        history.push_back((checker.clone(), params.summiter_points));
+                    } else if resultAssessment.CheckReject.contains(&checker) {
+                        s_dishonest.push_back(checker.clone());
+                        fined.push_back(checker.clone());
+                        history.push_back((checker.clone(), -params.summiter_points));
                     } else {
-                        return (2, betData.clone().amount_bet); // loser and honest
+                        s_noVote.push_back(checker.clone());
+                        fined.push_back(checker.clone());
+                        history.push_back((checker.clone(), -params.summiter_points));
                     }
                 }
-                if !resultAssessment.UsersApprove.contains(&user)
-                    && !resultAssessment.UsersReject.contains(&user)
-                {
-                    if betData.bet == xresult.result {
-                        return (4, betData.clone().amount_bet); // winner and honest
+
+                for outcome in 0..outcomes {
+                    let dishonest =
+                        storage::get_reject_total(env.clone(), setting.clone(), outcome);
+                    amount_gain_pool += dishonest;
+                    trust_taken_pool += dishonest;
+                }
+                for outcome in 0..outcomes {
+                    let amountBet =
+                        storage::get_approve_total(env.clone(), setting.clone(), outcome);
+                    let (win, _, loss) = Self::settled_stakes(
+                        amountBet,
+                        Self::outcome_settlement(
+                            market.clone(),
+                            setting.clone(),
+                            outcome,
+                            resultGame.clone(),
+                        ),
+                    );
+                    amount_gain_pool += loss;
+                    losers_honest_pool += loss;
+                    winner_pool += win;
+                }
+            }
+            2 => {
+                // summiter
+                s_honest.push_back(summiter.clone());
+                //Checkers
+                for checker in checkers.iter() {
+                    if resultAssessment.CheckApprove.contains(&checker) {
+                        s_honest.push_back(checker.clone());
+                        history.push_back((checker.clone(), params.summiter_points));
+                    } else if resultAssessment.CheckReject.contains(&checker) {
+                        s_dishonest.push_back(checker.clone());
+                        fined.push_back(checker.clone());
+                        history.push_back((checker.clone(), -params.summiter_points));
                     } else {
-                        return (5, 0); // loser and honest
+                        s_noVote.push_back(checker.clone());
+                        fined.push_back(checker.clone());
+                        history.push_back((checker.clone(), params.summiter_points));
                     }
+                }
+                for outcome in 0..outcomes {
+                    let amountBet =
+                        storage::get_approve_total(env.clone(), setting.clone(), outcome);
+                    let (win, _, loss) = Self::settled_stakes(
+                        amountBet,
+                        Self::outcome_settlement(
+                            market.clone(),
+                            setting.clone(),
+                            outcome,
+                            resultGame.clone(),
+                        ),
+                    );
+                    amount_gain_pool += loss;
+                    losers_honest_pool += loss;
+                    winner_pool += win;
+                }
+            }
+            _ => {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
+        }
+        // the summiters are judged by the first setting distributed, not again for every setting
+        if !result.distribution_executed {
+            for user in fined.iter() {
+                add += Self::slash_stake(env.clone(), user.clone(), game_id.clone());
+            }
+            for (user, points) in history.iter() {
+                storage::set_history(env.clone(), user.clone(), points);
+            }
+        }
+        amount_gain_pool += add;
+        amount_gain_pool += storage::get_Fine(env.clone(), game_id.clone());
+        storage::zero_Fine(env.clone(), game_id.clone());
+        let mut summiter_retribution = 0;
+        let mut protocol_retribution = (amount_gain_pool * params.protocol_fee) / 100;
+        if winner_pool == 0 && losers_honest_pool == 0 {
+            if s_honest.len() != 0 {
+                if summiter == admin {
+                    protocol_retribution = amount_gain_pool;
                 } else {
-                    panic_with_error!(&env, BettingError::InvalidInputError);
+                    summiter_retribution = (amount_gain_pool * params.no_winner_summiter_fee) / 100;
+                    for honest in s_honest.iter() {
+                        let amount = summiter_retribution / s_honest.len() as i128;
+                        storage::add_ClaimSummiter(env.clone(), honest.clone(), amount);
+                    }
+                    protocol_retribution = amount_gain_pool - summiter_retribution;
+                }
+            }
+        } else if s_honest.len() != 0 {
+            if summiter != admin {
+                summiter_retribution = (amount_gain_pool * params.summiter_fee) / 100;
+                for honest in s_honest.iter() {
+                    let amount = summiter_retribution / s_honest.len() as i128;
+                    storage::add_ClaimSummiter(env.clone(), honest.clone(), amount);
+                }
+            }
+        }
+        // the honest summiters get their locked stake back
+        for honest in s_honest.iter() {
+            Self::unlock_stake(env.clone(), honest.clone(), game_id.clone());
+        }
+        amount_gain_pool -= protocol_retribution;
+        amount_gain_pool -= summiter_retribution;
+        let trust_amount = (trust_taken_pool * params.trust_percentage) / 100;
+        storage::add_ClaimProtocol(env.clone(), protocol_retribution);
+        storage::add_ClaimProtocolTrust(env.clone(), trust_amount);
+        storage::save_complain(env.clone(), game_id.clone(), complain);
+        storage::save_winnerPool(env.clone(), setting.clone(), winner_pool);
+        storage::save_loserPool(env.clone(), setting.clone(), losers_honest_pool);
+        storage::set_pool_total(env.clone(), setting.clone(), amount_gain_pool);
+        storage::set_settingDistributed(env.clone(), setting.clone());
+        storage::distribution_ResultGame(env.clone(), game_id.clone());
+        result.distribution_executed = true;
+        storage::set_ResultGame(env.clone(), result.clone());
+        BettingEvents::game_setting_distributed(&env, setting);
+    }
+
+    /*
+       @dev Function to determine the kind of user based on their bet and assessment
+       @param env The contract environment
+       @param user The address of the user
+       @param setting The setting ID of the bet
+       @return A tuple containing the kind of user and the amount bet (if applicable)
+    */
+    fn what_kind_user(env: Env, user: Address, setting: i128) -> (UserKind, i128) {
+        let bets: Vec<Bet> = storage::get_Bets(env.clone(), user.clone(), setting.clone());
+        if bets.is_empty() {
+            return (UserKind::NoBet, 0);
+        }
+        let betData: Bet = bets.get(0).unwrap();
+        // the user is a winner when any of his positions gets money back
+        let mut amountBet: i128 = 0;
+        let mut winner = false;
+        for position in bets.iter() {
+            amountBet += position.amount_bet;
+            if Self::bet_settlement(env.clone(), position.clone()) != Settlement::Loss {
+                winner = true;
+            }
+        }
+        let (exist, startTime, endTime, summiter, checkers, _) =
+            storage::existBet(env.clone(), betData.clone().gameid);
+        if !exist {
+            panic_with_error!(&env, BettingError::GameDoesNotExist);
+        }
+        let xresult: ResultGame = storage::get_ResultGame(env.clone(), betData.clone().gameid);
+        let resultAssessment: ResultAssessment =
+            storage::get_ResultAssessment(env.clone(), betData.clone().gameid);
+        let complain = storage::get_complain(env.clone(), betData.clone().gameid);
+        let approved = resultAssessment.UsersApprove.contains(&user);
+        let rejected = resultAssessment.UsersReject.contains(&user);
+        match complain {
+            0 => {
+                if approved {
+                    return (UserKind::Dishonest, 0);
+                }
+                if rejected {
+                    if winner {
+                        return (UserKind::WinnerHonest, amountBet);
+                    } else {
+                        return (UserKind::LoserHonest, amountBet);
+                    }
                 }
             }
             1 => {
-                if resultAssessment.UsersApprove.contains(&user) {
-                    if betData.bet == xresult.result {
-                        return (1, betData.clone().amount_bet); // winner and honest
+                if approved {
+                    if winner {
+                        return (UserKind::WinnerHonest, amountBet);
                     } else {
-                        return (2, betData.clone().amount_bet); // loser and honest
+                        return (UserKind::LoserHonest, amountBet);
                     }
                 }
-                if resultAssessment.UsersReject.contains(&user) {
-                    return (3, 0); // user dishonest
+                if rejected {
+                    return (UserKind::Dishonest, 0);
                 }
-                if !resultAssessment.UsersApprove.contains(&user)
-                    && !resultAssessment.UsersReject.contains(&user)
-                {
-                    if betData.bet == xresult.result {
-                        return (4, betData.clone().amount_bet); // winner ?
+            }
+            2 => {
+                if approved {
+                    if winner {
+                        return (UserKind::WinnerHonest, amountBet);
                     } else {
-                        return (5, 0); // loser ?
+                        return (UserKind::LoserHonest, amountBet);
                     }
-                } else {
+                }
+                if rejected {
                     panic_with_error!(&env, BettingError::InvalidInputError);
                 }
             }
-            2 => {
-                if resultAssessment.UsersApprove.contains(&user) {
-                    if betData.bet == xresult.result {
-                        return (1, betData.clone().amount_bet); // winner and honest
-                    } else {
-                        return (2, betData.clone().amount_bet); // loser and honest
-                    }
-                }
-                if !resultAssessment.UsersApprove.contains(&user)
-                    && !resultAssessment.UsersReject.contains(&user)
-                {
-                    if betData.bet == xresult.result {
-                        return (4, betData.clone().amount_bet); // winner ?
-                    } else {
-                        return (5, 0); // loser ?
-                    }
-                } else {
-                    panic_with_error!(&env, BettingError::InvalidInputError);
-                }
+            _ => {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
+        }
+        // the user didn't assess the result
+        if winner {
+            (UserKind::WinnerNoVote, amountBet)
+        } else {
+            (UserKind::LoserNoVote, 0)
+        }
+    }
+    /*
+       @dev Function to compute what a user gets back from a distributed setting
+       @param env The contract environment
+       @param user The address of the user
+       @param setting The setting ID of the bet
+       @return The claim with the kind of user, the usd and trust amounts and the honesty points
+    */
+    fn user_claim(env: Env, user: Address, setting: i128) -> ClaimPreview {
+        let claimed = storage::get_didUserWithdraw(env.clone(), user.clone(), setting.clone());
+        let mut preview = ClaimPreview {
+            setting: setting.clone(),
+            kind: UserKind::NoBet,
+            settlement: Settlement::Loss,
+            amountUsd: 0,
+            amountTrust: 0,
+            points: 0,
+            claimed,
+        };
+        let bets: Vec<Bet> = storage::get_Bets(env.clone(), user.clone(), setting.clone());
+        if bets.is_empty() {
+            return preview;
+        }
+        if !storage::get_settingDistributed(env.clone(), setting.clone()) {
+            panic_with_error!(&env, BettingError::GameNoResult);
+        }
+        let (kindofUser, amountBet) =
+            Self::what_kind_user(env.clone(), user.clone(), setting.clone());
+        // the positions of the user are settled one by one and added up
+        let mut settlement = Settlement::Loss;
+        let mut winStake: i128 = 0;
+        let mut pushStake: i128 = 0;
+        let mut lossStake: i128 = 0;
+        for position in bets.iter() {
+            let positionSettlement = Self::bet_settlement(env.clone(), position.clone());
+            let (win, push, loss) = Self::settled_stakes(position.amount_bet, positionSettlement);
+            winStake += win;
+            pushStake += push;
+            lossStake += loss;
+            if positionSettlement < settlement {
+                settlement = positionSettlement;
+            }
+        }
+        let winner_pool = storage::get_winnerPool(env.clone(), setting.clone());
+        let loser_pool = storage::get_loserPool(env.clone(), setting.clone());
+        let amount_share = storage::get_pool_total(env.clone(), setting.clone());
+        let params: Params = storage::get_game_params(env.clone(), bets.get(0).unwrap().gameid);
+        match kindofUser {
+            UserKind::WinnerNoVote => {
+                // user gets the stake he didn't lose back minus the penalty and no trust back
+                preview.amountUsd = ((winStake + pushStake) * (100 - params.no_vote_penalty)) / 100;
             }
-            _ => {
-                panic_with_error!(&env, BettingError::InvalidInputError);
+            UserKind::LoserHonest | UserKind::WinnerHonest => {
+                // user gets back the stake he didn't lose, his share of the pool and trust tokens
+                // losers share the pool when nobody won
+                let mut user_amount = 0;
+                if winStake != 0 {
+                    user_amount = (winStake * amount_share) / winner_pool;
+                } else if winner_pool == 0 && lossStake != 0 {
+                    user_amount = (lossStake * amount_share) / loser_pool;
+                }
+                preview.amountUsd = winStake + pushStake + user_amount;
+                preview.amountTrust = (amountBet * params.trust_percentage) / 100;
+                preview.points = params.honest_points;
             }
+            UserKind::NoBet | UserKind::Dishonest | UserKind::LoserNoVote => {}
         }
+        preview.kind = kindofUser;
+        preview.settlement = settlement;
+        preview
     }
     /*
-       @dev Function to select summiter and checkers for a game
+       @dev Function to start the selection of the summiter and checkers of a game when it is activated
+       It only records the entropy, the ledger and the pool of candidates of the activation, the draw is made by
+       reveal_seed or fallback_seed and the admin is the summiter until then
        @param env The contract environment
        @param game_id The ID of the game
     */
@@ -1240,50 +2826,75 @@ impl BettingContract {
         if !exist {
             panic_with_error!(&env, BettingError::GameDoesNotExist);
         }
-        let mut leaderboard: Vec<(Address, i128)> = storage::get_leaderboard(env.clone());
+        // the entropy comes from the host, the activating transaction can't predict it
+        let entropy: BytesN<32> = env.prng().gen();
+        let mut draw: SelectionDraw = storage::get_selectionDraw(env.clone(), game_id.clone());
+        let zero = BytesN::from_array(&env, &[0; 32]);
+        // the secret of a finished draw is known, a new draw can't rely on it
+        if draw.drawn {
+            draw.commitment = zero.clone();
+        }
+        // the draw is made by a later call, the activating transaction can't revert until it gets the summiters it wants
+        draw.entropy = entropy;
+        draw.drawn = false;
+        draw.activated_ledger = env.ledger().sequence();
+        // the candidates are fixed now, joining or leaving the leaderboard once the seed is known can't steer the draw
+        draw.pool = Self::selection_pool(
+            env.clone(),
+            storage::get_selection_policy(env.clone()),
+            Vec::new(&env),
+        );
+        draw.reveal_deadline = 0;
+        if draw.commitment != zero {
+            // the secret must be revealed before the game starts
+            draw.reveal_deadline =
+                (env.ledger().timestamp() as u32 + SEED_REVEAL_WINDOW).min(startTime);
+        }
+        storage::set_selectionDraw(env.clone(), game_id.clone(), draw);
+        storage::update_game(
+            env.clone(),
+            game_id,
+            storage::get_admin(env.clone()),
+            Vec::new(&env),
+        );
+    }
+    /*
+       @dev Function to draw the summiter and checkers of a game with a seeded PRNG from the pool taken at its activation,
+       the candidates that can no longer lock the stake are left out. The seed is recorded so anyone can repeat the draw
+       @param env The contract environment
+       @param game_id The ID of the game
+       @param seed The seed of the draw
+    */
+    fn draw_summiters(env: Env, game_id: i128, seed: BytesN<32>) {
         let adminAdr: Address = storage::get_admin(env.clone());
         let mut main_summiter: Address = adminAdr.clone();
         let mut selected_Summitters: Vec<Address> = Vec::new(&env);
 
-        // Limit to top 5
-        if leaderboard.len() != 0 {
-            let mut top = Vec::new(&env);
-            if leaderboard.len() < 10 {
-                top = leaderboard.clone();
-            } else {
-                for i in 0..9 {
-                    if let Some((addr, score)) = leaderboard.get(i) {
-                        if score == 0 {
-                            break;
-                        }
-                        top.push_back((addr.clone(), score));
-                    }
-                }
+        let policy: SelectionPolicy = storage::get_selection_policy(env.clone());
+        let lock = storage::get_stake_lock(env.clone());
+        let mut pool: Vec<(Address, i128)> = Vec::new(&env);
+        for (addr, score) in storage::get_selectionDraw(env.clone(), game_id.clone())
+            .pool
+            .iter()
+        {
+            let bond: SummiterBond = storage::get_summiterBond(env.clone(), addr.clone());
+            if !bond.unbonding && storage::get_stakeAmount_user(env.clone(), addr.clone()) >= lock {
+                pool.push_back((addr, score));
             }
-
-            let sequence = env.ledger().sequence();
-            let timestamp = env.ledger().timestamp() as u32;
-            let mut rng = (sequence + timestamp) % (top.len() as u32);
-            for k in 0..4 {
-                let pick = rng % top.len();
-                let (addr, score) = top.get(pick).unwrap();
-                if k == 0 {
-                    main_summiter = addr.clone();
-                    storage::set_stakeAmount_user_game(env.clone(), addr.clone(), game_id.clone());
-                } else {
-                    selected_Summitters.push_back(addr.clone());
-                    storage::set_stakeAmount_user_game(env.clone(), addr.clone(), game_id.clone());
-                }
-
-                top.remove(pick); // remove picked
-                if let Some(pos) = leaderboard.iter().position(|(a, _)| a == addr) {
-                    leaderboard.remove(pos.try_into().unwrap());
-                }
-                if top.len() == 0 {
-                    break;
-                }
+        }
+        let checkers = Self::checker_count(env.clone(), game_id.clone(), policy);
+        env.prng().seed(Bytes::from(seed.clone()));
+        for k in 0..checkers + 1 {
+            if pool.len() == 0 {
+                break;
+            }
+            let addr = Self::pick_candidate(env.clone(), &mut pool);
+            if k == 0 {
+                main_summiter = addr.clone();
+            } else {
+                selected_Summitters.push_back(addr.clone());
             }
-            storage::set_leaderboard(env.clone(), leaderboard);
+            Self::lock_stake(env.clone(), addr.clone(), game_id.clone(), lock);
         }
         storage::update_game(
             env.clone(),
@@ -1291,8 +2902,600 @@ impl BettingContract {
             main_summiter.clone(),
             selected_Summitters.clone(),
         );
+        let mut draw: SelectionDraw = storage::get_selectionDraw(env.clone(), game_id.clone());
+        draw.seed = seed.clone();
+        draw.drawn = true;
+        storage::set_selectionDraw(env.clone(), game_id.clone(), draw);
+        BettingEvents::selection_seed(&env, game_id, seed);
         BettingEvents::summiters_seleted(&env, game_id, selected_Summitters, main_summiter);
     }
+    /*
+       @dev Function to read the number of checkers a game has to be judged by, which grows with the value bet on it
+       It is only read by the draw, so the checkers are fixed then and a later bet can't trigger a draw it can foresee
+       @param env The contract environment
+       @param game_id The ID of the game
+       @param policy SelectionPolicy The selection policy
+    */
+    fn checker_count(env: Env, game_id: i128, policy: SelectionPolicy) -> u32 {
+        let mut checkers = policy.checkers;
+        if policy.value_per_checker > 0 {
+            let extra =
+                storage::get_total_bet(env.clone(), game_id.clone()) / policy.value_per_checker;
+            checkers = (checkers as i128 + extra).min(policy.max_checkers as i128) as u32;
+        }
+        checkers
+    }
+    /*
+       @dev Function to build the pool a draw is made from out of the best ranked candidates that can back a game
+       The ones without enough free stake to lock, a low history, selected recently or already selected are skipped
+       @param env The contract environment
+       @param policy SelectionPolicy The selection policy
+       @param exclude Vec<Address> The addresses already selected for the game
+    */
+    fn selection_pool(
+        env: Env,
+        policy: SelectionPolicy,
+        exclude: Vec<Address>,
+    ) -> Vec<(Address, i128)> {
+        let index: LeaderboardIndex = storage::get_leaderboard_index(env.clone());
+        let now = env.ledger().timestamp() as u32;
+        let lock = storage::get_stake_lock(env.clone());
+        let mut pool: Vec<(Address, i128)> = Vec::new(&env);
+        // the pages are only read until the pool is full
+        'pages: for (page, _, _) in index.pages.iter() {
+            for (addr, score) in storage::get_leaderboard_page(env.clone(), page).iter() {
+                if pool.len() == policy.pool_size {
+                    break 'pages;
+                }
+                if exclude.contains(&addr)
+                    || storage::get_stakeAmount_user(env.clone(), addr.clone()) < lock
+                    || storage::get_history(env.clone(), addr.clone()) < policy.min_history
+                {
+                    continue;
+                }
+                let bond: SummiterBond = storage::get_summiterBond(env.clone(), addr.clone());
+                if bond.last_selected != 0 && bond.last_selected + policy.exclusion_window > now {
+                    continue;
+                }
+                pool.push_back((addr.clone(), score));
+            }
+        }
+        pool
+    }
+    /*
+       @dev Function to pick a candidate of a non empty pool with the seeded PRNG and remove it from the pool
+       The chance of a candidate is proportional to his score
+       @param env The contract environment
+       @param pool Vec<(Address, i128)> The pool of candidates
+    */
+    fn pick_candidate(env: Env, pool: &mut Vec<(Address, i128)>) -> Address {
+        let mut total: i128 = 0;
+        for (_, score) in pool.iter() {
+            total += score.max(1);
+        }
+        let range = match u64::try_from(total) {
+            Ok(range) => range,
+            Err(_) => panic_with_error!(&env, BettingError::OverflowError),
+        };
+        let mut ticket = env.prng().gen_range::<u64>(0..range) as i128;
+        let mut pick: u32 = 0;
+        for (_, score) in pool.iter() {
+            if ticket < score.max(1) {
+                break;
+            }
+            ticket -= score.max(1);
+            pick += 1;
+        }
+        let (addr, _) = pool.get(pick).unwrap();
+        pool.remove(pick); // remove picked
+        addr
+    }
+    /*
+       @dev Function to give the bond of a seed commitment back to the admin or forfeit it to the fines of the game
+       @param env The contract environment
+       @param game_id The ID of the game
+       @param refund Whether the bond goes back to the admin
+    */
+    fn settle_seed_bond(env: Env, game_id: i128, refund: bool) {
+        let mut draw: SelectionDraw = storage::get_selectionDraw(env.clone(), game_id.clone());
+        if draw.bond == 0 {
+            return;
+        }
+        if refund {
+            let contract_address = env.current_contract_address();
+            let usd = storage::get_usd(env.clone());
+            let adminAdr: Address = storage::get_admin(env.clone());
+            Self::moveToken(&env, &usd, &contract_address, &adminAdr, &draw.bond);
+        } else {
+            storage::add_Fine(env.clone(), game_id.clone(), draw.bond);
+            BettingEvents::seed_withheld(&env, game_id, draw.bond);
+        }
+        draw.bond = 0;
+        storage::set_selectionDraw(env.clone(), game_id.clone(), draw);
+    }
+    /*
+       @dev Function to know whether a position was placed before the last reschedule of its game
+       and can still be cancelled without fee
+       @param env The contract environment
+       @param game_id The ID of the game
+       @param bet_id The ID of the position
+    */
+    fn in_opt_out_window(env: Env, game_id: i128, bet_id: i128) -> bool {
+        let rescheduledAt = storage::get_gameRescheduledAt(env.clone(), game_id.clone());
+        storage::get_gameReschedules(env.clone(), game_id.clone()) != 0
+            && storage::get_betPlacedAt(env.clone(), bet_id) < rescheduledAt
+            && env.ledger().timestamp() as u32 <= rescheduledAt + RESCHEDULE_OPT_OUT_WINDOW
+    }
+    /*
+       @dev Function to give back the stakes of the summiter and checkers of a game to the leaderboard
+       @param env The contract environment
+       @param game_id The ID of the game
+    */
+    fn release_summiters(env: Env, game_id: i128) {
+        let (_, _, _, summiter, checkers, _) = storage::existBet(env.clone(), game_id.clone());
+        let mut summiters: Vec<Address> = checkers.clone();
+        summiters.push_front(summiter.clone());
+        for user in summiters.iter() {
+            Self::unlock_stake(env.clone(), user.clone(), game_id.clone());
+        }
+    }
+    /*
+       @dev Function to lock part of the free stake of a summiter for a game
+       @param env The contract environment
+       @param user The address of the summiter
+       @param game_id The ID of the game
+       @param amount The amount locked
+    */
+    fn lock_stake(env: Env, user: Address, game_id: i128, amount: i128) {
+        let free = storage::get_stakeAmount_user(env.clone(), user.clone());
+        storage::set_stakeAmount_user_game(env.clone(), user.clone(), game_id.clone(), amount);
+        storage::set_stakeAmount_user(env.clone(), user.clone(), free - amount);
+        let mut bond: SummiterBond = storage::get_summiterBond(env.clone(), user.clone());
+        bond.games.push_back(game_id.clone());
+        bond.last_selected = env.ledger().timestamp() as u32;
+        storage::set_summiterBond(env.clone(), user.clone(), bond);
+        // the score follows the free stake
+        Self::adduser_board(&env, user, 0);
+    }
+    /*
+       @dev Function to give back the stake a summiter locked for a game to his free stake
+       @param env The contract environment
+       @param user The address of the summiter
+       @param game_id The ID of the game
+    */
+    fn unlock_stake(env: Env, user: Address, game_id: i128) {
+        let stake = Self::take_locked_stake(env.clone(), user.clone(), game_id);
+        if stake != 0 {
+            Self::adduser_board(&env, user, stake);
+        }
+    }
+    /*
+       @dev Function to take the stake a summiter locked for a game when he is fined
+       @param env The contract environment
+       @param user The address of the summiter
+       @param game_id The ID of the game
+       @return The amount slashed
+    */
+    fn slash_stake(env: Env, user: Address, game_id: i128) -> i128 {
+        Self::take_locked_stake(env, user, game_id)
+    }
+    /*
+       @dev Function to clear the stake a summiter locked for a game so it is only unlocked or slashed once
+       @param env The contract environment
+       @param user The address of the summiter
+       @param game_id The ID of the game
+       @return The amount that was locked
+    */
+    fn take_locked_stake(env: Env, user: Address, game_id: i128) -> i128 {
+        let stake = storage::get_stakeAmount_user_game(env.clone(), user.clone(), game_id.clone());
+        if stake != 0 {
+            storage::zero_stakeAmount_user_game(env.clone(), user.clone(), game_id.clone());
+            let mut bond: SummiterBond = storage::get_summiterBond(env.clone(), user.clone());
+            if let Some(pos) = bond.games.first_index_of(&game_id) {
+                bond.games.remove(pos);
+                storage::set_summiterBond(env.clone(), user.clone(), bond);
+            }
+        }
+        stake
+    }
+    /*
+       @dev Function to read the state of a setting whether it is private or public
+       @param env The contract environment
+       @param setting The ID of the setting
+       @return A tuple containing the type of setting, the game id and if the setting is active
+    */
+    fn setting_status(env: Env, setting: i128) -> (BetType, i128, bool) {
+        let privateBet: PrivateBet = storage::get_PrivateBet(env.clone(), setting.clone());
+        if privateBet.id != 0 {
+            return (BetType::Private, privateBet.gameid, privateBet.active);
+        }
+        let publicBet: PublicBet = storage::get_PublicBet(env.clone(), setting.clone());
+        if publicBet.id != 0 {
+            return (BetType::Public, publicBet.gameid, publicBet.active);
+        }
+        panic_with_error!(&env, BettingError::SettingBetDoesNotExist);
+    }
+    /*
+       @dev Function to activate or deactivate a setting whether it is private or public
+       @param env The contract environment
+       @param setting The ID of the setting
+       @param active bool The new state of the setting
+    */
+    fn set_setting_active(env: Env, setting: i128, active: bool) {
+        let (betType, _, _) = Self::setting_status(env.clone(), setting.clone());
+        if betType == BetType::Private {
+            storage::active_private_setting(env.clone(), setting, active);
+        } else {
+            storage::active_public_setting(env.clone(), setting, active);
+        }
+    }
+    /*
+       @dev Function to track again the activation of a setting after a position has been withdrawn or moved
+       A setting is active while at least two outcomes have stakes
+       @param env The contract environment
+       @param setting The ID of the setting
+    */
+    fn refresh_setting_activity(env: Env, setting: i128) {
+        let (_, gameId, active) = Self::setting_status(env.clone(), setting.clone());
+        let outcomes: u32 =
+            Self::market_outcomes(Self::setting_market(env.clone(), setting.clone()));
+        let mut staked: u32 = 0;
+        for outcome in 0..outcomes {
+            if storage::get_not_assesed_yet(env.clone(), setting.clone(), outcome) > 0 {
+                if staked == 0 {
+                    storage::set_lastBet(env.clone(), setting.clone(), outcome);
+                }
+                staked += 1;
+            }
+        }
+        if staked == 0 {
+            storage::remove_lastBet(env.clone(), setting.clone());
+        }
+        if staked > 1 && !active {
+            Self::set_setting_active(env.clone(), setting.clone(), true);
+            BettingEvents::active_setting(&env, gameId.clone(), setting.clone());
+            let (_, _, _, _, _, gameActive) = storage::existBet(env.clone(), gameId.clone());
+            //if the game hasn't been actived we select the summiter
+            if !gameActive {
+                Self::select_summiter(env.clone(), gameId.clone())
+            }
+        } else if staked < 2 && active {
+            Self::set_setting_active(env.clone(), setting.clone(), false);
+            BettingEvents::inactive_setting(&env, gameId.clone(), setting.clone());
+            // once no setting keeps the game active its summiters get their stake back,
+            // a new activation draws them again
+            let stillActive = Self::game_settings(env.clone(), gameId.clone())
+                .iter()
+                .any(|other| Self::setting_status(env.clone(), other).2);
+            if !stillActive {
+                Self::release_summiters(env.clone(), gameId.clone());
+                storage::deactivate_game(
+                    env.clone(),
+                    gameId.clone(),
+                    storage::get_admin(env.clone()),
+                );
+                let mut draw: SelectionDraw =
+                    storage::get_selectionDraw(env.clone(), gameId.clone());
+                // the secret of a finished draw is known, the next activation can't use it
+                if draw.drawn {
+                    draw.commitment = BytesN::from_array(&env, &[0; 32]);
+                }
+                draw.drawn = false;
+                draw.entropy = BytesN::from_array(&env, &[0; 32]);
+                draw.reveal_deadline = 0;
+                draw.activated_ledger = 0;
+                draw.pool = Vec::new(&env);
+                storage::set_selectionDraw(env.clone(), gameId.clone(), draw);
+            }
+        }
+    }
+    /*
+       @dev Function to list all the settings (public and private) of a game
+       @param env The contract environment
+       @param game_id The ID of the game
+    */
+    fn game_settings(env: Env, game_id: i128) -> Vec<i128> {
+        let mut settings: Vec<i128> = storage::get_publicSettingList(env.clone(), game_id.clone());
+        settings.append(&storage::get_privateSettingList(
+            env.clone(),
+            game_id.clone(),
+        ));
+        settings
+    }
+    /*
+       @dev Function to read the market of a setting whether it is private or public
+       @param env The contract environment
+       @param setting The ID of the setting
+    */
+    fn setting_market(env: Env, setting: i128) -> MarketType {
+        let (betType, _, _) = Self::setting_status(env.clone(), setting.clone());
+        if betType == BetType::Private {
+            storage::get_PrivateBet(env.clone(), setting).market
+        } else {
+            storage::get_PublicBet(env.clone(), setting).market
+        }
+    }
+    /*
+       @dev Function to count the outcomes a user can bet on in a market, bets use the index of the outcome
+       @param market MarketType The market of the setting
+    */
+    fn market_outcomes(market: MarketType) -> u32 {
+        match market {
+            MarketType::Winner => 3,
+            MarketType::Totals(_) | MarketType::Handicap(_) | MarketType::DrawNoBet => 2,
+            // the last outcome is any other score
+            MarketType::ExactScore(scores) => scores.len() + 1,
+            MarketType::Custom(options) => options.len(),
+        }
+    }
+    /*
+       @dev Function to check the market of a new setting
+       Totals lines are in hundredths and must end in .5 so there is never a push
+       Handicap lines are in hundredths and go in quarters of a goal
+       Exact scores can't be negative or repeated and custom markets need at least two outcomes
+       @param env The contract environment
+       @param market MarketType The market of the setting
+    */
+    fn validate_market(env: Env, market: MarketType) {
+        match market {
+            MarketType::Totals(line) => {
+                if line <= 0 || line % 100 != 50 {
+                    panic_with_error!(&env, BettingError::InvalidInputError);
+                }
+            }
+            MarketType::Handicap(line) => {
+                if line % 25 != 0 {
+                    panic_with_error!(&env, BettingError::InvalidInputError);
+                }
+            }
+            MarketType::ExactScore(scores) => {
+                if scores.len() == 0 {
+                    panic_with_error!(&env, BettingError::InvalidInputError);
+                }
+                for (i, score) in scores.iter().enumerate() {
+                    if score.local < 0
+                        || score.away < 0
+                        || scores.first_index_of(&score) != Some(i as u32)
+                    {
+                        panic_with_error!(&env, BettingError::InvalidInputError);
+                    }
+                }
+            }
+            MarketType::Custom(options) => {
+                if options.len() < 2 {
+                    panic_with_error!(&env, BettingError::InvalidInputError);
+                }
+            }
+            MarketType::Winner | MarketType::DrawNoBet => {}
+        }
+    }
+    /*
+       @dev Function to get the winner of the game (1X2) from the final score
+       @param score Score The final score of the game
+    */
+    fn score_result(score: Score) -> BetKey {
+        if score.local > score.away {
+            BetKey::Team_local
+        } else if score.local < score.away {
+            BetKey::Team_away
+        } else {
+            BetKey::Draw
+        }
+    }
+    /*
+       @dev Function to check a summited result, scores can't be negative, the half time
+       can't have more goals than the final score and the declared result must match the score
+       A cancelled game doesn't need a score
+       @param env The contract environment
+       @param result ResultGame The result of the game
+    */
+    fn validate_score(env: Env, result: ResultGame) {
+        if result.score.local < 0 || result.score.away < 0 {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        if let HalfTime::Score(half_time) = result.clone().half_time {
+            if half_time.local < 0
+                || half_time.away < 0
+                || half_time.local > result.score.local
+                || half_time.away > result.score.away
+            {
+                panic_with_error!(&env, BettingError::InvalidInputError);
+            }
+        }
+        if result.result == BetKey::Cancel {
+            return;
+        }
+        if result.result != Self::score_result(result.clone().score) {
+            panic_with_error!(&env, BettingError::InvalidInputError);
+        }
+        // custom settings can't be settled from the score, the result must say which outcome won
+        for setting in Self::game_settings(env.clone(), result.clone().gameid).iter() {
+            if let MarketType::Custom(options) = Self::setting_market(env.clone(), setting) {
+                match result.outcomes.get(setting) {
+                    Some(outcome) if outcome < options.len() => {}
+                    _ => panic_with_error!(&env, BettingError::InvalidInputError),
+                }
+            }
+        }
+    }
+    /*
+       @dev Function to settle an outcome of a market from the result of the game
+       @param market MarketType The market of the setting
+       @param setting i128 The ID of the setting
+       @param outcome u32 The index of the outcome to settle
+       @param result ResultGame The result of the game
+    */
+    fn outcome_settlement(
+        market: MarketType,
+        setting: i128,
+        outcome: u32,
+        result: ResultGame,
+    ) -> Settlement {
+        let score = result.clone().score;
+        match market {
+            MarketType::Winner => {
+                let winner = match Self::score_result(score) {
+                    BetKey::Team_local => WINNER_LOCAL,
+                    BetKey::Team_away => WINNER_AWAY,
+                    _ => WINNER_DRAW,
+                };
+                Self::win_or_loss(outcome == winner)
+            }
+            MarketType::Totals(line) => {
+                let over = (score.local + score.away) * 100 > line;
+                Self::win_or_loss(over == (outcome == TOTALS_OVER))
+            }
+            MarketType::DrawNoBet => match Self::score_result(score) {
+                BetKey::Team_local => Self::win_or_loss(outcome == SIDE_LOCAL),
+                BetKey::Team_away => Self::win_or_loss(outcome == SIDE_AWAY),
+                _ => Settlement::Push,
+            },
+            MarketType::Handicap(line) => {
+                // goal margin of the chosen team with the handicap applied, in hundredths
+                let margin = if outcome == SIDE_LOCAL {
+                    (score.local - score.away) * 100 + line
+                } else {
+                    (score.away - score.local) * 100 - line
+                };
+                if line % 50 == 0 {
+                    return Self::margin_settlement(margin);
+                }
+                // a quarter line is half the stake on each of the two closest half lines
+                match (
+                    Self::margin_settlement(margin - 25),
+                    Self::margin_settlement(margin + 25),
+                ) {
+                    (Settlement::Win, Settlement::Win) => Settlement::Win,
+                    (Settlement::Push, Settlement::Win) => Settlement::HalfWin,
+                    (Settlement::Loss, Settlement::Push) => Settlement::HalfLoss,
+                    _ => Settlement::Loss,
+                }
+            }
+            MarketType::ExactScore(scores) => {
+                let winner = scores.first_index_of(&score).unwrap_or(scores.len());
+                Self::win_or_loss(outcome == winner)
+            }
+            MarketType::Custom(_) => {
+                Self::win_or_loss(result.outcomes.get(setting) == Some(outcome))
+            }
+        }
+    }
+    /*
+       @dev Function to settle an outcome that can only win or lose
+       @param won bool If the outcome won
+    */
+    fn win_or_loss(won: bool) -> Settlement {
+        if won {
+            Settlement::Win
+        } else {
+            Settlement::Loss
+        }
+    }
+    /*
+       @dev Function to settle a margin in hundredths of goals
+       @param margin i128 The margin of the chosen team with the handicap applied
+    */
+    fn margin_settlement(margin: i128) -> Settlement {
+        if margin > 0 {
+            Settlement::Win
+        } else if margin == 0 {
+            Settlement::Push
+        } else {
+            Settlement::Loss
+        }
+    }
+    /*
+       @dev Function to settle the bet of a user from the stored result of the game
+       @param env The contract environment
+       @param betData Bet The bet of the user
+    */
+    fn bet_settlement(env: Env, betData: Bet) -> Settlement {
+        let result: ResultGame = storage::get_ResultGame(env.clone(), betData.clone().gameid);
+        let market = Self::setting_market(env.clone(), betData.clone().Setting);
+        Self::outcome_settlement(market, betData.clone().Setting, betData.bet, result)
+    }
+    /*
+       @dev Function to get the winnings of a parlay at MAX_PARLAY_ODDS, reserved from the pool while it is open
+       @param amount i128 The amount bet on the parlay
+    */
+    fn parlay_reserve(amount: i128) -> i128 {
+        (amount * (MAX_PARLAY_ODDS - EVEN_ODDS)) / EVEN_ODDS
+    }
+    /*
+       @dev Function to get the odds of a parlay leg in hundredths from the pools of its setting
+       The odds are what the setting pays its winners, the losing stakes net of the protocol and summiter cut,
+       and an outcome with less than MIN_LEG_OUTCOME_SHARE of the setting pays even odds, so a dust bet can't price a leg
+       A leg is void (even odds) when its game is cancelled, the setting never got active, or no result
+       was summited or the result is still disputed after the assessment time, otherwise it waits for
+       the distribution of the setting
+       @param env The contract environment
+       @param leg ParlayLeg The leg of the parlay
+    */
+    fn leg_odds(env: Env, leg: ParlayLeg) -> i128 {
+        let publicBet: PublicBet = storage::get_PublicBet(env.clone(), leg.setting);
+        let result: ResultGame = storage::get_ResultGame(env.clone(), publicBet.gameid);
+        let (_, _, endTime, _, _, _) = storage::existBet(env.clone(), publicBet.gameid);
+        let timeline: Timeline = storage::get_timeline(env.clone(), publicBet.gameid);
+        // a leg on a game cancelled by the admin is void
+        if storage::get_gameCancelled(env.clone(), publicBet.gameid) {
+            return EVEN_ODDS;
+        }
+        if !storage::get_settingDistributed(env.clone(), leg.setting) {
+            // the supreme court may never rule on a disputed result
+            if endTime + timeline.assessment_window < env.ledger().timestamp() as u32
+                && (result.pause
+                    || result.id == 0
+                    || result.result == BetKey::Cancel
+                    || !publicBet.active)
+            {
+                return EVEN_ODDS;
+            }
+            panic_with_error!(&env, BettingError::ParlayLegNotSettled);
+        }
+        let mut settingTotal = 0;
+        let mut outcomeTotal = 0;
+        for outcome in 0..Self::market_outcomes(publicBet.clone().market) {
+            let total = storage::get_not_assesed_yet(env.clone(), leg.setting, outcome)
+                + storage::get_approve_total(env.clone(), leg.setting, outcome)
+                + storage::get_reject_total(env.clone(), leg.setting, outcome);
+            settingTotal += total;
+            if outcome == leg.outcome {
+                outcomeTotal = total;
+            }
+        }
+        // too little was bet on the outcome in the setting for its pools to price it
+        let mut odds = EVEN_ODDS;
+        if outcomeTotal != 0 && outcomeTotal * 100 >= settingTotal * MIN_LEG_OUTCOME_SHARE {
+            let params: Params = storage::get_game_params(env.clone(), publicBet.gameid);
+            let net = ((settingTotal - outcomeTotal)
+                * (100 - params.protocol_fee - params.summiter_fee))
+                / 100;
+            odds = EVEN_ODDS + (net * EVEN_ODDS) / outcomeTotal;
+        }
+        match Self::outcome_settlement(publicBet.market, leg.setting, leg.outcome, result) {
+            Settlement::Win => odds,
+            Settlement::HalfWin => (odds + EVEN_ODDS) / 2,
+            Settlement::Push => EVEN_ODDS,
+            Settlement::HalfLoss => EVEN_ODDS / 2,
+            Settlement::Loss => 0,
+        }
+    }
+    /*
+       @dev Function to split a stake by its settlement
+       @param amount i128 The amount bet
+       @param settlement Settlement How the bet was settled
+       @return A tuple with the part of the stake that won, the part refunded and the part lost
+    */
+    fn settled_stakes(amount: i128, settlement: Settlement) -> (i128, i128, i128) {
+        let half = amount / 2;
+        match settlement {
+            Settlement::Win => (amount, 0, 0),
+            Settlement::HalfWin => (half, amount - half, 0),
+            Settlement::Push => (0, amount, 0),
+            Settlement::HalfLoss => (0, half, amount - half),
+            Settlement::Loss => (0, 0, amount),
+        }
+    }
     /*
     @dev Function to move tokens from one address to another
     @param env The contract environment
@@ -1306,48 +3509,38 @@ impl BettingContract {
         token.transfer(from, to, amount);
     }
     /*
-        @dev This function add a user to the leaderboard or update his score if he is already in
+        @dev This function add stake to a user and put him in the leaderboard or update his score if he is already in
+        A user that is unbonding only gets the stake back, he is not listed again
         @param env Environment
         @param user Address The address of the user
-        @param stakeAmount i128 The amount staked by the user
+        @param stakeAmount i128 The amount added to the stake of the user
     */
     fn adduser_board(env: &Env, user: Address, stakeAmount: i128) {
         /*We nee to set a amount to request for the summiter rol */
+        // a summiter that already withdrew is never listed again
+        if !storage::has_summiterBond(env.clone(), user.clone()) {
+            return;
+        }
+        let stakeAmount = storage::get_stakeAmount_user(env.clone(), user.clone()) + stakeAmount;
+        let mut bond: SummiterBond = storage::get_summiterBond(env.clone(), user.clone());
+        if bond.unbonding {
+            storage::set_stakeAmount_user(env.clone(), user.clone(), stakeAmount);
+            return;
+        }
         // ✅ Get history map (user → score history)
         let history: i128 = storage::get_history(env.clone(), user.clone());
 
         // ✅ Weighted score calculation
         let new_score = (history * VOTE_HISTORY_WEIGHT + stakeAmount * SCORE_HISTORY_WEIGHT) / 100;
 
-        // ✅ Leaderboard vector
-        let mut leaderboard: Vec<(Address, i128)> = storage::get_leaderboard(env.clone());
-
-        // Remove old entry for this user
-        let mut i = 0;
-        while i < leaderboard.len() {
-            let (addr, _) = leaderboard.get(i).unwrap();
-            if addr == user {
-                leaderboard.remove(i);
-                break;
-            }
-            i += 1;
-        }
-
-        // Find position to insert in descending order
-        let mut insert_index = leaderboard.len();
-        for idx in 0..leaderboard.len() {
-            let (_, score) = leaderboard.get(idx).unwrap();
-            if new_score > score || new_score == score {
-                insert_index = idx;
-                break;
-            }
+        // Move the user to the page of his new score
+        if bond.listed {
+            storage::board_remove(env.clone(), user.clone(), bond.score);
         }
-
-        // Insert at correct position
-        leaderboard.insert(insert_index, (user.clone(), new_score));
-
-        // Save leaderboard
-        storage::set_leaderboard(env.clone(), leaderboard);
+        storage::board_insert(env.clone(), user.clone(), new_score);
+        bond.listed = true;
+        bond.score = new_score;
+        storage::set_summiterBond(env.clone(), user.clone(), bond);
         storage::set_stakeAmount_user(env.clone(), user.clone(), stakeAmount);
     }
 }
@@ -25,7 +25,6 @@ pub enum BettingError {
     PublicSettingNotFound = 206,
     GameHasAlreadyStarted = 207,
     GameIsActive = 208,
-    GameHasNotStarted = 209,
     GameHasAlreadySet = 210,
     GameHasNotFinished = 211,
     NotAllowToSummitResult = 212,
@@ -43,4 +42,18 @@ pub enum BettingError {
     UnknownSigner = 224,
     GameResultAlreadySet = 225,
     BetNotFound = 226,
+    ParlayLegNotSettled = 227,
+    PrivateBet_TooMuchToBet = 228,
+    GameCancelled = 229,
+    ParamsTimelocked = 230,
+    SummiterUnbonding = 231,
+    StakeLocked = 232,
+    SeedMismatch = 233,
+    SignatureExpired = 234,
+    SignatureReused = 235,
+    InvalidProof = 236,
+    SettingIdAlreadyUsed = 237,
+    RotationTimelocked = 238,
+    GamesStillAssigned = 239,
+    PositionInOptOutWindow = 240,
 }
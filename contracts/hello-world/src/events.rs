@@ -1,8 +1,8 @@
 use crate::types::{
-    AssessmentKey, Bet, BetKey, BetType, ClaimType, DataKey, Game, LastB, PrivateBet, PublicBet,
-    ResultAssessment, ResultGame,
+    AssessmentKey, Bet, BetKey, BetType, ClaimType, DataKey, Game, LastB, Params, PrivateBet,
+    PublicBet, ResultAssessment, ResultGame, SelectionPolicy, Settlement, Timeline, TimelineScope,
 };
-use soroban_sdk::{contractevent, vec, Address, Env, String, Symbol, Vec};
+use soroban_sdk::{contractevent, vec, Address, BytesN, Env, String, Symbol, Vec};
 
 #[contractevent(topics = ["BettingGame", "Seleted_Suimmiters"], data_format = "vec")]
 struct SummitersSeletedEvent {
@@ -11,6 +11,43 @@ struct SummitersSeletedEvent {
     main: Address,
 }
 
+#[contractevent(topics = ["BettingGame", "Seed_Committed"], data_format = "vec")]
+struct SeedCommittedEvent {
+    game_id: i128,
+    commitment: BytesN<32>,
+}
+#[contractevent(topics = ["BettingGame", "Seed_Withheld"], data_format = "vec")]
+struct SeedWithheldEvent {
+    game_id: i128,
+    bond: i128,
+}
+#[contractevent(topics = ["BettingGame", "Selection_Seed"], data_format = "vec")]
+struct SelectionSeedEvent {
+    game_id: i128,
+    seed: BytesN<32>,
+}
+
+#[contractevent(topics = ["BettingGame", "Game_Publisher"], data_format = "vec")]
+struct GamePublisherEvent {
+    game_id: i128,
+    key_id: u32,
+}
+#[contractevent(topics = ["BettingGame", "Fixtures_Published"], data_format = "vec")]
+struct FixturesPublishedEvent {
+    root: BytesN<32>,
+    key_id: u32,
+}
+#[contractevent(topics = ["BettingGame", "Publisher_Added"], data_format = "vec")]
+struct PublisherAddedEvent {
+    key_id: u32,
+    pubkey: BytesN<32>,
+    leagues: Vec<i128>,
+}
+#[contractevent(topics = ["BettingGame", "Publisher_Revoked"], data_format = "single-value")]
+struct PublisherRevokedEvent {
+    key_id: u32,
+}
+
 #[contractevent(topics = ["BettingGame", "Game_Set"], data_format = "single-value")]
 struct GameSetEvent {
     game_id: i128,
@@ -23,6 +60,11 @@ struct PrivateSettingEvent {
     admin: Address,
     minAmount: i128,
 }
+#[contractevent(topics = ["BettingGame", "Public_Setting"], data_format = "vec")]
+struct PublicSettingEvent {
+    game_id: i128,
+    setting: i128,
+}
 #[contractevent(topics = ["BettingGame", "Private_Setting_newUser"], data_format = "vec")]
 struct NewUserAddedPrivateEvent {
     game_id: i128,
@@ -63,11 +105,138 @@ struct GameAllUserHaveVotedEvent {
 struct GameSettingDistributedEvent {
     setting: i128,
 }
+#[contractevent(topics = ["BettingGame", "User_Claim"], data_format = "vec")]
+struct UserClaimEvent {
+    setting: i128,
+    user: Address,
+    settlement: Settlement,
+    amountUsd: i128,
+    amountTrust: i128,
+}
+#[contractevent(topics = ["BettingGame", "Parlay_Bet"], data_format = "vec")]
+struct ParlayBetEvent {
+    user: Address,
+    parlay: i128,
+    amount: i128,
+}
+#[contractevent(topics = ["BettingGame", "Parlay_Claim"], data_format = "vec")]
+struct ParlayClaimEvent {
+    user: Address,
+    parlay: i128,
+    payout: i128,
+}
+#[contractevent(topics = ["BettingGame", "Parlay_Pool"], data_format = "single-value")]
+struct ParlayPoolEvent {
+    pool: i128,
+}
+#[contractevent(topics = ["BettingGame", "Bet_Cancel"], data_format = "vec")]
+struct BetCancelEvent {
+    user: Address,
+    setting: i128,
+    outcome: u32,
+    refund: i128,
+    fee: i128,
+}
+#[contractevent(topics = ["BettingGame", "Bet_Update"], data_format = "vec")]
+struct BetUpdateEvent {
+    user: Address,
+    setting: i128,
+    outcome: u32,
+    newOutcome: u32,
+}
+#[contractevent(topics = ["BettingGame", "Inactive_Setting"], data_format = "vec")]
+struct Inactive_SettingEvent {
+    game_id: i128,
+    Setting: i128,
+}
+#[contractevent(topics = ["BettingGame", "Game_Cancelled"], data_format = "vec")]
+struct GameCancelledEvent {
+    game_id: i128,
+    reason: String,
+}
+#[contractevent(topics = ["BettingGame", "Game_Rescheduled"], data_format = "vec")]
+struct GameRescheduledEvent {
+    game_id: i128,
+    startTime: u32,
+    endTime: u32,
+    reselected: bool,
+}
+#[contractevent(topics = ["BettingGame", "Timeline_Set"], data_format = "vec")]
+struct TimelineSetEvent {
+    scope: TimelineScope,
+    timeline: Timeline,
+}
+#[contractevent(topics = ["BettingGame", "Admin_Proposed"], data_format = "vec")]
+struct AdminProposedEvent {
+    admin: Address,
+    proposed: Address,
+}
+#[contractevent(topics = ["BettingGame", "Admin_Transferred"], data_format = "vec")]
+struct AdminTransferredEvent {
+    previous: Address,
+    admin: Address,
+}
+#[contractevent(topics = ["BettingGame", "Pubkey_Proposed"], data_format = "vec")]
+struct PubkeyProposedEvent {
+    pubkey: BytesN<32>,
+    effective_at: u32,
+}
+#[contractevent(topics = ["BettingGame", "Pubkey_Rotated"], data_format = "vec")]
+struct PubkeyRotatedEvent {
+    previous: BytesN<32>,
+    pubkey: BytesN<32>,
+}
+#[contractevent(topics = ["BettingGame", "Court_Proposed"], data_format = "vec")]
+struct CourtProposedEvent {
+    supreme_court: Address,
+    effective_at: u32,
+}
+#[contractevent(topics = ["BettingGame", "Court_Rotated"], data_format = "vec")]
+struct CourtRotatedEvent {
+    previous: Address,
+    supreme_court: Address,
+}
+#[contractevent(topics = ["BettingGame", "Params_Proposed"], data_format = "vec")]
+struct ParamsProposedEvent {
+    params: Params,
+    effective_at: u32,
+}
+#[contractevent(topics = ["BettingGame", "Params_Applied"], data_format = "single-value")]
+struct ParamsAppliedEvent {
+    params: Params,
+}
+#[contractevent(topics = ["BettingGame", "Unbond_Requested"], data_format = "vec")]
+struct UnbondRequestedEvent {
+    user: Address,
+    amount: i128,
+    available_at: u32,
+}
+#[contractevent(topics = ["BettingGame", "Stake_Withdrawn"], data_format = "vec")]
+struct StakeWithdrawnEvent {
+    user: Address,
+    amount: i128,
+}
 #[contractevent(topics = ["BettingGame", "Game_StakeMinAmount"], data_format = "single-value")]
 struct StakeMinAmountdEvent {
     NewAmount: i128,
 }
 
+#[contractevent(topics = ["BettingGame", "Game_StakeLock"], data_format = "single-value")]
+struct StakeLockEvent {
+    amount: i128,
+}
+
+#[contractevent(topics = ["BettingGame", "Selection_Policy"], data_format = "single-value")]
+struct SelectionPolicyEvent {
+    policy: SelectionPolicy,
+}
+
+#[contractevent(topics = ["BettingGame", "Leaderboard_Migrated"], data_format = "vec")]
+struct LeaderboardMigratedEvent {
+    moved: u32,
+    remaining: u32,
+}
+
 pub struct BettingEvents {}
 
 impl BettingEvents {
@@ -79,9 +248,39 @@ impl BettingEvents {
         }
         .publish(&e);
     }
+    pub fn seed_committed(e: &Env, game_id: i128, commitment: BytesN<32>) {
+        SeedCommittedEvent {
+            game_id,
+            commitment,
+        }
+        .publish(&e);
+    }
+    pub fn seed_withheld(e: &Env, game_id: i128, bond: i128) {
+        SeedWithheldEvent { game_id, bond }.publish(&e);
+    }
+    pub fn selection_seed(e: &Env, game_id: i128, seed: BytesN<32>) {
+        SelectionSeedEvent { game_id, seed }.publish(&e);
+    }
     pub fn game_set(e: &Env, game_id: i128) {
         GameSetEvent { game_id }.publish(&e);
     }
+    pub fn game_publisher(e: &Env, game_id: i128, key_id: u32) {
+        GamePublisherEvent { game_id, key_id }.publish(&e);
+    }
+    pub fn fixtures_published(e: &Env, root: BytesN<32>, key_id: u32) {
+        FixturesPublishedEvent { root, key_id }.publish(&e);
+    }
+    pub fn publisher_added(e: &Env, key_id: u32, pubkey: BytesN<32>, leagues: Vec<i128>) {
+        PublisherAddedEvent {
+            key_id,
+            pubkey,
+            leagues,
+        }
+        .publish(&e);
+    }
+    pub fn publisher_revoked(e: &Env, key_id: u32) {
+        PublisherRevokedEvent { key_id }.publish(&e);
+    }
     pub fn private_setting(e: &Env, game_id: i128, setting: i128, admin: Address, minAmount: i128) {
         PrivateSettingEvent {
             game_id,
@@ -91,6 +290,9 @@ impl BettingEvents {
         }
         .publish(&e);
     }
+    pub fn public_setting(e: &Env, game_id: i128, setting: i128) {
+        PublicSettingEvent { game_id, setting }.publish(&e);
+    }
     pub fn new_user_added_private(e: &Env, game_id: i128, setting: i128, Newuser: Address) {
         NewUserAddedPrivateEvent {
             game_id,
@@ -116,6 +318,15 @@ impl BettingEvents {
     pub fn set_stake_amount(e: &Env, NewAmount: i128) {
         StakeMinAmountdEvent { NewAmount }.publish(&e);
     }
+    pub fn leaderboard_migrated(e: &Env, moved: u32, remaining: u32) {
+        LeaderboardMigratedEvent { moved, remaining }.publish(&e);
+    }
+    pub fn selection_policy(e: &Env, policy: SelectionPolicy) {
+        SelectionPolicyEvent { policy }.publish(&e);
+    }
+    pub fn set_stake_lock(e: &Env, amount: i128) {
+        StakeLockEvent { amount }.publish(&e);
+    }
     pub fn game_result_supreme(e: &Env, game_id: i128, result: BetKey) {
         GameResultSupremeEvent { game_id, result }.publish(&e);
     }
@@ -128,4 +339,141 @@ impl BettingEvents {
     pub fn all_vote(e: &Env, game_id: i128) {
         GameAllUserHaveVotedEvent { game_id }.publish(&e);
     }
+    pub fn user_claim(
+        e: &Env,
+        setting: i128,
+        user: Address,
+        settlement: Settlement,
+        amountUsd: i128,
+        amountTrust: i128,
+    ) {
+        UserClaimEvent {
+            setting,
+            user,
+            settlement,
+            amountUsd,
+            amountTrust,
+        }
+        .publish(&e);
+    }
+    pub fn parlay_bet(e: &Env, user: Address, parlay: i128, amount: i128) {
+        ParlayBetEvent {
+            user,
+            parlay,
+            amount,
+        }
+        .publish(&e);
+    }
+    pub fn parlay_claim(e: &Env, user: Address, parlay: i128, payout: i128) {
+        ParlayClaimEvent {
+            user,
+            parlay,
+            payout,
+        }
+        .publish(&e);
+    }
+    pub fn parlay_pool(e: &Env, pool: i128) {
+        ParlayPoolEvent { pool }.publish(&e);
+    }
+    pub fn bet_cancel(
+        e: &Env,
+        user: Address,
+        setting: i128,
+        outcome: u32,
+        refund: i128,
+        fee: i128,
+    ) {
+        BetCancelEvent {
+            user,
+            setting,
+            outcome,
+            refund,
+            fee,
+        }
+        .publish(&e);
+    }
+    pub fn bet_update(e: &Env, user: Address, setting: i128, outcome: u32, newOutcome: u32) {
+        BetUpdateEvent {
+            user,
+            setting,
+            outcome,
+            newOutcome,
+        }
+        .publish(&e);
+    }
+    pub fn inactive_setting(e: &Env, game_id: i128, Setting: i128) {
+        Inactive_SettingEvent { game_id, Setting }.publish(&e);
+    }
+    pub fn params_proposed(e: &Env, params: Params, effective_at: u32) {
+        ParamsProposedEvent {
+            params,
+            effective_at,
+        }
+        .publish(&e);
+    }
+    pub fn params_applied(e: &Env, params: Params) {
+        ParamsAppliedEvent { params }.publish(&e);
+    }
+    pub fn admin_proposed(e: &Env, admin: Address, proposed: Address) {
+        AdminProposedEvent { admin, proposed }.publish(&e);
+    }
+    pub fn admin_transferred(e: &Env, previous: Address, admin: Address) {
+        AdminTransferredEvent { previous, admin }.publish(&e);
+    }
+    pub fn pubkey_proposed(e: &Env, pubkey: BytesN<32>, effective_at: u32) {
+        PubkeyProposedEvent {
+            pubkey,
+            effective_at,
+        }
+        .publish(&e);
+    }
+    pub fn pubkey_rotated(e: &Env, previous: BytesN<32>, pubkey: BytesN<32>) {
+        PubkeyRotatedEvent { previous, pubkey }.publish(&e);
+    }
+    pub fn court_proposed(e: &Env, supreme_court: Address, effective_at: u32) {
+        CourtProposedEvent {
+            supreme_court,
+            effective_at,
+        }
+        .publish(&e);
+    }
+    pub fn court_rotated(e: &Env, previous: Address, supreme_court: Address) {
+        CourtRotatedEvent {
+            previous,
+            supreme_court,
+        }
+        .publish(&e);
+    }
+    pub fn unbond_requested(e: &Env, user: Address, amount: i128, available_at: u32) {
+        UnbondRequestedEvent {
+            user,
+            amount,
+            available_at,
+        }
+        .publish(&e);
+    }
+    pub fn stake_withdrawn(e: &Env, user: Address, amount: i128) {
+        StakeWithdrawnEvent { user, amount }.publish(&e);
+    }
+    pub fn timeline_set(e: &Env, scope: TimelineScope, timeline: Timeline) {
+        TimelineSetEvent { scope, timeline }.publish(&e);
+    }
+    pub fn game_cancelled(e: &Env, game_id: i128, reason: String) {
+        GameCancelledEvent { game_id, reason }.publish(&e);
+    }
+    pub fn game_rescheduled(
+        e: &Env,
+        game_id: i128,
+        startTime: u32,
+        endTime: u32,
+        reselected: bool,
+    ) {
+        GameRescheduledEvent {
+            game_id,
+            startTime,
+            endTime,
+            reselected,
+        }
+        .publish(&e);
+    }
 }
@@ -1,8 +1,18 @@
+use crate::errors::BettingError;
 use crate::types::{
-    AssessmentKey, Bet, BetKey, BetType, ClaimType, DataKey, Game, LastB, PrivateBet, PublicBet,
-    ResultAssessment, ResultGame,
+    AssessmentKey, Bet, BetKey, BetType, ClaimType, CourtRotation, DataKey, Game, HalfTime,
+    IdCounters, IdKind, LastB, LeaderboardIndex, MarketType, Params, ParamsProposal, Parlay,
+    PrivateBet, PubkeyRotation, PublicBet, Publisher, ResultAssessment, ResultGame, Score,
+    SelectionDraw, SelectionPolicy, SummiterBond, Timeline, TimelineScope,
+};
+use crate::Constants::{
+    CANCEL_FEE_PERCENTAGE, FIFTY_PERCENT, FIFTY_POINTS, FIRST_SETTING_ID, HUNDRED_POINTS,
+    LEADERBOARD_PAGE_SIZE, ONE_HOUR_SECONDS, SELECTION_CHECKERS, SELECTION_POOL_SIZE,
+    STAKE_LOCK_AMOUNT, TEN_PERCENT, TRUST_TOKEN_PERCENTAGE, TWENTY_PERCENT, TWENTY_POINTS,
+};
+use soroban_sdk::{
+    panic_with_error, symbol_short, vec, Address, BytesN, Env, Map, String, Symbol, Vec,
 };
-use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol, Vec};
 const ADMIN_KEY: Symbol = Symbol::short("ADMIN");
 const ADMIN_PUB_KEY: Symbol = Symbol::short("Adm_key");
 
@@ -13,6 +23,14 @@ const LEADERBOARD: Symbol = symbol_short!("LB");
 const SUMITTERS_HISTORY: Symbol = symbol_short!("H_S");
 const COUNTER: Symbol = symbol_short!("COUNTER");
 const x: Symbol = symbol_short!("x");
+const PARAMS: Symbol = symbol_short!("PARAMS");
+const PARAMS_PROPOSAL: Symbol = symbol_short!("PARAMS_P");
+const STAKE_LOCK: Symbol = symbol_short!("LOCK");
+const SELECTION_POLICY: Symbol = symbol_short!("POLICY");
+const ADMIN_PROPOSAL: Symbol = symbol_short!("ADMIN_P");
+const PUBKEY_ROTATION: Symbol = symbol_short!("KEY_P");
+const SUPREME_ROTATION: Symbol = symbol_short!("SUPREME_P");
+const PARLAY_RESERVED: Symbol = symbol_short!("PARLAY_R");
 pub fn get_dummyusser(env: &Env) -> Address {
     Address::from_string(&String::from_str(
         env,
@@ -70,6 +88,43 @@ pub fn get_admin_pubkey(env: Env) -> BytesN<32> {
         .get(&ADMIN_PUB_KEY)
         .unwrap_or_else(|| panic!("contract not initialized"))
 }
+pub fn set_admin(env: Env, admin: Address) {
+    env.storage().instance().set(&ADMIN_KEY, &admin);
+}
+pub fn set_admin_pubkey(env: Env, admin_pubkey: BytesN<32>) {
+    env.storage().instance().set(&ADMIN_PUB_KEY, &admin_pubkey);
+}
+pub fn set_supreme(env: Env, supreme_court: Address) {
+    env.storage().instance().set(&SUPREME_KEY, &supreme_court);
+}
+// governance changes waiting to be accepted or for their timelock
+pub fn set_admin_proposal(env: Env, admin: Address) {
+    env.storage().instance().set(&ADMIN_PROPOSAL, &admin);
+}
+pub fn get_admin_proposal(env: Env) -> Option<Address> {
+    env.storage().instance().get(&ADMIN_PROPOSAL)
+}
+pub fn remove_admin_proposal(env: Env) {
+    env.storage().instance().remove(&ADMIN_PROPOSAL);
+}
+pub fn set_pubkey_rotation(env: Env, rotation: PubkeyRotation) {
+    env.storage().instance().set(&PUBKEY_ROTATION, &rotation);
+}
+pub fn get_pubkey_rotation(env: Env) -> Option<PubkeyRotation> {
+    env.storage().instance().get(&PUBKEY_ROTATION)
+}
+pub fn remove_pubkey_rotation(env: Env) {
+    env.storage().instance().remove(&PUBKEY_ROTATION);
+}
+pub fn set_court_rotation(env: Env, rotation: CourtRotation) {
+    env.storage().instance().set(&SUPREME_ROTATION, &rotation);
+}
+pub fn get_court_rotation(env: Env) -> Option<CourtRotation> {
+    env.storage().instance().get(&SUPREME_ROTATION)
+}
+pub fn remove_court_rotation(env: Env) {
+    env.storage().instance().remove(&SUPREME_ROTATION);
+}
 pub fn get_history(env: Env, user: Address) -> i128 {
     env.storage()
         .persistent()
@@ -87,17 +142,169 @@ pub fn set_history(env: Env, user: Address, points: i128) {
         .persistent()
         .set(&DataKey::History_Summiter(user.clone()), &total_history);
 }
-pub fn get_leaderboard(env: Env) -> Vec<(Address, i128)> {
+// leaderboard of the previous versions, kept in one vector until it is migrated
+pub fn get_legacy_leaderboard(env: Env) -> Vec<(Address, i128)> {
     env.storage()
         .persistent()
         .get(&DataKey::GameSummiters)
         .unwrap_or(Vec::new(&env))
 }
-pub fn set_leaderboard(env: Env, leaderboard: Vec<(Address, i128)>) -> bool {
+pub fn set_legacy_leaderboard(env: Env, leaderboard: Vec<(Address, i128)>) -> bool {
+    if leaderboard.len() == 0 {
+        env.storage().persistent().remove(&DataKey::GameSummiters);
+    } else {
+        env.storage()
+            .persistent()
+            .set(&DataKey::GameSummiters, &leaderboard);
+    }
+    true
+}
+// the index grows with the leaderboard, so it is kept in its own persistent entry instead of the instance,
+// under a symbol key because DataKey is at the 50 variants a contract type can have
+pub fn get_leaderboard_index(env: Env) -> LeaderboardIndex {
     env.storage()
         .persistent()
-        .set(&DataKey::GameSummiters, &leaderboard);
-    true
+        .get(&LEADERBOARD)
+        .unwrap_or(LeaderboardIndex {
+            pages: Vec::new(&env),
+            next_page: 0,
+            total: 0,
+        })
+}
+pub fn set_leaderboard_index(env: Env, index: LeaderboardIndex) {
+    env.storage().persistent().set(&LEADERBOARD, &index);
+}
+pub fn get_leaderboard_page(env: Env, page: u32) -> Vec<(Address, i128)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LeaderboardPage(page))
+        .unwrap_or(Vec::new(&env))
+}
+pub fn set_leaderboard_page(env: Env, page: u32, entries: Vec<(Address, i128)>) {
+    if entries.len() == 0 {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LeaderboardPage(page));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&DataKey::LeaderboardPage(page), &entries);
+    }
+}
+// binary search of the first page whose lowest score is not above the score
+fn find_leaderboard_page(index: &LeaderboardIndex, score: i128) -> u32 {
+    let mut low = 0;
+    let mut high = index.pages.len();
+    while low < high {
+        let mid = (low + high) / 2;
+        let (_, lowest, _) = index.pages.get(mid).unwrap();
+        if lowest <= score {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    low
+}
+pub fn board_insert(env: Env, user: Address, score: i128) {
+    let mut index = get_leaderboard_index(env.clone());
+    if index.pages.len() == 0 {
+        let page = index.next_page;
+        set_leaderboard_page(env.clone(), page, vec![&env, (user, score)]);
+        index.pages.push_back((page, score, 1));
+        index.next_page += 1;
+        index.total += 1;
+        set_leaderboard_index(env, index);
+        return;
+    }
+    let pos = find_leaderboard_page(&index, score).min(index.pages.len() - 1);
+    let (page, _, _) = index.pages.get(pos).unwrap();
+    let mut entries = get_leaderboard_page(env.clone(), page);
+    // the user goes in front of the ones with the same score
+    let mut at = entries.len();
+    for i in 0..entries.len() {
+        let (_, other) = entries.get(i).unwrap();
+        if score >= other {
+            at = i;
+            break;
+        }
+    }
+    entries.insert(at, (user, score));
+    if entries.len() > LEADERBOARD_PAGE_SIZE {
+        let half = entries.len() / 2;
+        let upper = entries.slice(0..half);
+        let lower = entries.slice(half..entries.len());
+        let new_page = index.next_page;
+        index
+            .pages
+            .set(pos, (page, upper.last().unwrap().1, upper.len()));
+        index
+            .pages
+            .insert(pos + 1, (new_page, lower.last().unwrap().1, lower.len()));
+        index.next_page += 1;
+        set_leaderboard_page(env.clone(), page, upper);
+        set_leaderboard_page(env.clone(), new_page, lower);
+    } else {
+        index
+            .pages
+            .set(pos, (page, entries.last().unwrap().1, entries.len()));
+        set_leaderboard_page(env.clone(), page, entries);
+    }
+    index.total += 1;
+    set_leaderboard_index(env, index);
+}
+pub fn board_remove(env: Env, user: Address, score: i128) -> bool {
+    let mut index = get_leaderboard_index(env.clone());
+    // the pages before hold higher scores only, the same score can go on over the next pages
+    let mut pos = find_leaderboard_page(&index, score);
+    while pos < index.pages.len() {
+        let (page, lowest, _) = index.pages.get(pos).unwrap();
+        let mut entries = get_leaderboard_page(env.clone(), page);
+        if let Some(i) = entries.iter().position(|(a, _)| a == user) {
+            entries.remove(i as u32);
+            if entries.len() == 0 {
+                index.pages.remove(pos);
+            } else {
+                index
+                    .pages
+                    .set(pos, (page, entries.last().unwrap().1, entries.len()));
+            }
+            set_leaderboard_page(env.clone(), page, entries);
+            index.total -= 1;
+            set_leaderboard_index(env, index);
+            return true;
+        }
+        if lowest < score {
+            break;
+        }
+        pos += 1;
+    }
+    false
+}
+pub fn get_leaderboard_range(env: Env, start: u32, limit: u32) -> Vec<(Address, i128)> {
+    let index = get_leaderboard_index(env.clone());
+    let mut result = Vec::new(&env);
+    let mut skipped: u32 = 0;
+    for (page, _, size) in index.pages.iter() {
+        if result.len() >= limit {
+            break;
+        }
+        if skipped + size <= start {
+            skipped += size;
+            continue;
+        }
+        for entry in get_leaderboard_page(env.clone(), page).iter() {
+            if skipped < start {
+                skipped += 1;
+                continue;
+            }
+            if result.len() >= limit {
+                break;
+            }
+            result.push_back(entry);
+        }
+    }
+    result
 }
 pub fn set_stakeAmount_user(env: Env, user: Address, stakeAmount: i128) {
     env.storage()
@@ -105,16 +312,63 @@ pub fn set_stakeAmount_user(env: Env, user: Address, stakeAmount: i128) {
         .set(&DataKey::StakeUserAmount(user), &stakeAmount);
 }
 
-pub fn set_stakeAmount_user_game(env: Env, user: Address, game: i128) {
-    let amount: i128 = env
-        .storage()
-        .persistent()
-        .get(&DataKey::StakeUserAmount(user.clone()))
-        .unwrap_or(0);
+pub fn set_stakeAmount_user_game(env: Env, user: Address, game: i128, amount: i128) {
     env.storage()
         .persistent()
         .set(&DataKey::StakeUserGameAmount(user, game), &amount);
 }
+pub fn get_stakeAmount_user(env: Env, user: Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::StakeUserAmount(user))
+        .unwrap_or(0)
+}
+pub fn get_selectionDraw(env: Env, game_id: i128) -> SelectionDraw {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SelectionDraw(game_id))
+        .unwrap_or(SelectionDraw {
+            commitment: BytesN::from_array(&env, &[0; 32]),
+            entropy: BytesN::from_array(&env, &[0; 32]),
+            seed: BytesN::from_array(&env, &[0; 32]),
+            drawn: false,
+            reveal_deadline: 0,
+            activated_ledger: 0,
+            bond: 0,
+            pool: Vec::new(&env),
+        })
+}
+pub fn set_selectionDraw(env: Env, game_id: i128, draw: SelectionDraw) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::SelectionDraw(game_id), &draw);
+}
+pub fn get_summiterBond(env: Env, user: Address) -> SummiterBond {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SummiterBond(user))
+        .unwrap_or(SummiterBond {
+            games: Vec::new(&env),
+            unbonding: false,
+            available_at: 0,
+            last_selected: 0,
+            listed: false,
+            score: 0,
+        })
+}
+pub fn has_summiterBond(env: Env, user: Address) -> bool {
+    env.storage().persistent().has(&DataKey::SummiterBond(user))
+}
+pub fn set_summiterBond(env: Env, user: Address, bond: SummiterBond) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::SummiterBond(user), &bond);
+}
+pub fn remove_summiterBond(env: Env, user: Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::SummiterBond(user));
+}
 pub fn get_stakeAmount_user_game(env: Env, user: Address, game: i128) -> i128 {
     let amount: i128 = env
         .storage()
@@ -123,6 +377,11 @@ pub fn get_stakeAmount_user_game(env: Env, user: Address, game: i128) -> i128 {
         .unwrap_or(0);
     amount
 }
+pub fn zero_stakeAmount_user_game(env: Env, user: Address, game: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::StakeUserGameAmount(user, game), &0i128);
+}
 pub fn set_Min_stakeAmount(env: Env, stakeAmount: i128) {
     env.storage()
         .persistent()
@@ -136,6 +395,108 @@ pub fn get_Min_stakeAmount(env: Env) -> i128 {
         .unwrap_or(0);
     amount
 }
+pub fn set_params(env: Env, params: Params) {
+    env.storage().instance().set(&PARAMS, &params);
+}
+pub fn set_stake_lock(env: Env, amount: i128) {
+    env.storage().instance().set(&STAKE_LOCK, &amount);
+}
+pub fn get_stake_lock(env: Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&STAKE_LOCK)
+        .unwrap_or(STAKE_LOCK_AMOUNT)
+}
+pub fn set_selection_policy(env: Env, policy: SelectionPolicy) {
+    env.storage().instance().set(&SELECTION_POLICY, &policy);
+}
+pub fn get_selection_policy(env: Env) -> SelectionPolicy {
+    env.storage()
+        .instance()
+        .get(&SELECTION_POLICY)
+        .unwrap_or(SelectionPolicy {
+            pool_size: SELECTION_POOL_SIZE,
+            checkers: SELECTION_CHECKERS,
+            value_per_checker: 0,
+            max_checkers: SELECTION_CHECKERS,
+            exclusion_window: 0,
+            min_history: i128::MIN,
+        })
+}
+// the constants are the parameters until the admin changes them
+pub fn get_params(env: Env) -> Params {
+    env.storage().instance().get(&PARAMS).unwrap_or(Params {
+        protocol_fee: TEN_PERCENT,
+        summiter_fee: TWENTY_PERCENT,
+        no_winner_summiter_fee: FIFTY_PERCENT,
+        trust_percentage: TRUST_TOKEN_PERCENTAGE,
+        no_vote_penalty: FIFTY_PERCENT,
+        cancel_fee: CANCEL_FEE_PERCENTAGE,
+        bet_points: TWENTY_POINTS,
+        honest_points: FIFTY_POINTS,
+        summiter_points: HUNDRED_POINTS,
+    })
+}
+pub fn set_params_proposal(env: Env, proposal: ParamsProposal) {
+    env.storage().instance().set(&PARAMS_PROPOSAL, &proposal);
+}
+pub fn get_params_proposal(env: Env) -> Option<ParamsProposal> {
+    env.storage().instance().get(&PARAMS_PROPOSAL)
+}
+pub fn remove_params_proposal(env: Env) {
+    env.storage().instance().remove(&PARAMS_PROPOSAL);
+}
+pub fn set_game_params(env: Env, game_id: i128, params: Params) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::GameParams(game_id), &params);
+}
+// a game settles with the parameters there were when it was set
+pub fn get_game_params(env: Env, game_id: i128) -> Params {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GameParams(game_id))
+        .unwrap_or(get_params(env.clone()))
+}
+pub fn set_timeline(env: Env, scope: TimelineScope, timeline: Timeline) {
+    let key = match scope {
+        TimelineScope::Default => DataKey::Timeline,
+        TimelineScope::League(league) => DataKey::LeagueTimeline(league),
+        TimelineScope::Game(game) => DataKey::GameTimeline(game),
+    };
+    env.storage().persistent().set(&key, &timeline);
+}
+// a game keeps the timeline it got when it was set, changing the league or the default one doesn't move its deadlines
+pub fn get_timeline(env: Env, game_id: i128) -> Timeline {
+    if let Some(timeline) = env
+        .storage()
+        .persistent()
+        .get(&DataKey::GameTimeline(game_id))
+    {
+        return timeline;
+    }
+    let game: Game = get_game(env.clone(), game_id);
+    resolve_timeline(env.clone(), game.league)
+}
+// the timeline of a new game comes from its league and then the default one
+pub fn resolve_timeline(env: Env, league: i128) -> Timeline {
+    if let Some(timeline) = env
+        .storage()
+        .persistent()
+        .get(&DataKey::LeagueTimeline(league))
+    {
+        return timeline;
+    }
+    env.storage()
+        .persistent()
+        .get(&DataKey::Timeline)
+        .unwrap_or(Timeline {
+            summit_window: ONE_HOUR_SECONDS,
+            checker_window: 2 * ONE_HOUR_SECONDS,
+            admin_window: 3 * ONE_HOUR_SECONDS,
+            assessment_window: 5 * ONE_HOUR_SECONDS,
+        })
+}
 pub fn update_game(env: Env, gameId: i128, summiter: Address, checkers: Vec<(Address)>) {
     env.storage()
         .persistent()
@@ -159,6 +520,56 @@ pub fn update_game(env: Env, gameId: i128, summiter: Address, checkers: Vec<(Add
             game
         });
 }
+pub fn deactivate_game(env: Env, gameId: i128, summiter: Address) {
+    let mut game: Game = get_game(env.clone(), gameId);
+    game.summiter = summiter;
+    game.active = false;
+    game.Checker = Vec::new(&env);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(gameId), &game);
+}
+pub fn reschedule_game(env: Env, gameId: i128, startTime: u32, endTime: u32) {
+    let mut game: Game = get_game(env.clone(), gameId);
+    game.startTime = startTime;
+    game.endTime = endTime;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(gameId), &game);
+    let reschedules = get_gameReschedules(env.clone(), gameId);
+    // the number of reschedules and the time of the last one
+    env.storage().persistent().set(
+        &DataKey::GameReschedules(gameId),
+        &(reschedules + 1, env.ledger().timestamp() as u32),
+    );
+}
+pub fn get_gameReschedules(env: Env, gameId: i128) -> u32 {
+    let (reschedules, _): (u32, u32) = env
+        .storage()
+        .persistent()
+        .get(&DataKey::GameReschedules(gameId))
+        .unwrap_or((0, 0));
+    reschedules
+}
+pub fn get_gameRescheduledAt(env: Env, gameId: i128) -> u32 {
+    let (_, rescheduledAt): (u32, u32) = env
+        .storage()
+        .persistent()
+        .get(&DataKey::GameReschedules(gameId))
+        .unwrap_or((0, 0));
+    rescheduledAt
+}
+pub fn set_betPlacedAt(env: Env, betId: i128, placedAt: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::BetPlacedAt(betId), &placedAt);
+}
+pub fn get_betPlacedAt(env: Env, betId: i128) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::BetPlacedAt(betId))
+        .unwrap_or(0)
+}
 pub fn add_privateSettingList(env: Env, gameId: i128, setting: i128) {
     let mut listedBet: Vec<(i128)> = env
         .storage()
@@ -180,28 +591,93 @@ pub fn get_privateSettingList(env: Env, gameId: i128) -> Vec<(i128)> {
     list
 }
 
-pub fn add_bet(env: Env, user: Address, bet: Bet) {
+pub fn add_publicSettingList(env: Env, gameId: i128, setting: i128) {
+    let mut listedBet: Vec<(i128)> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::PublicBetList(gameId))
+        .unwrap_or(Vec::new(&env));
+    listedBet.push_back(setting);
     env.storage()
         .persistent()
-        .set(&DataKey::Bet(user.clone(), bet.clone().Setting), &bet);
+        .set(&DataKey::PublicBetList(gameId), &listedBet);
 }
-pub fn get_bet(env: Env, user: Address, setting: i128) -> Bet {
-    let bet: Bet = env
+
+pub fn get_publicSettingList(env: Env, gameId: i128) -> Vec<(i128)> {
+    let list: Vec<(i128)> = env
         .storage()
         .persistent()
-        .get(&DataKey::Bet(user.clone(), setting))
-        .unwrap_or_else(|| panic!("No bet found for this user"));
-    bet
+        .get(&DataKey::PublicBetList(gameId))
+        .unwrap_or(Vec::new(&env));
+    list
+}
+
+// a bet on an outcome the user already holds tops up that position
+// returns true when it is the first position of the user in the setting
+pub fn add_bet(env: Env, user: Address, bet: Bet) -> bool {
+    let mut bets: Vec<Bet> = get_Bets(env.clone(), user.clone(), bet.clone().Setting);
+    let first = bets.is_empty();
+    let mut toppedUp = false;
+    for i in 0..bets.len() {
+        let mut position = bets.get(i).unwrap();
+        if position.bet == bet.bet {
+            position.amount_bet += bet.amount_bet;
+            bets.set(i, position);
+            toppedUp = true;
+        }
+    }
+    if !toppedUp {
+        bets.push_back(bet.clone());
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Bet(user.clone(), bet.clone().Setting), &bets);
+    first
+}
+// removes the position of the user on an outcome, returns an empty bet when there is none
+pub fn remove_bet(env: Env, user: Address, setting: i128, outcome: u32) -> Bet {
+    let mut bets: Vec<Bet> = get_Bets(env.clone(), user.clone(), setting);
+    let mut removed = Bet {
+        id: 0,
+        gameid: 0,
+        betType: BetType::Public,
+        Setting: 0,
+        bet: 0,
+        amount_bet: 0,
+    };
+    for i in 0..bets.len() {
+        let position = bets.get(i).unwrap();
+        if position.bet == outcome {
+            removed = position;
+            bets.remove(i);
+            break;
+        }
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Bet(user.clone(), setting), &bets);
+    removed
+}
+pub fn set_lastBet(env: Env, setting: i128, outcome: u32) {
+    env.storage().persistent().set(
+        &DataKey::lastBet(setting),
+        &LastB {
+            id: setting,
+            lastBet: outcome,
+        },
+    );
+}
+pub fn remove_lastBet(env: Env, setting: i128) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::lastBet(setting));
 }
 pub fn does_bet_active(env: Env, bet: Bet) -> bool {
     let lastBet: LastB = env
         .storage()
         .persistent()
         .get(&DataKey::lastBet(bet.clone().Setting))
-        .unwrap_or(LastB {
-            id: 0,
-            lastBet: BetKey::Team_local,
-        });
+        .unwrap_or(LastB { id: 0, lastBet: 0 });
     if lastBet.clone().id == 0 {
         // it means this is the fisrt bet for this setting
         env.storage().persistent().set(
@@ -233,7 +709,10 @@ pub fn active_private_setting(env: Env, setting: i128, active: bool) {
                 )),
                 description: String::from_slice(&env, "No private bet found"),
                 amount_bet_min: 0,
+                amount_bet_max: 0,
+                fixed_stake: false,
                 users_invated: Vec::new(&env),
+                market: MarketType::Winner,
             });
             res.active = active;
             res
@@ -249,6 +728,7 @@ pub fn active_public_setting(env: Env, setting: i128, active: bool) {
                 gameid: 0,
                 active: false,
                 description: String::from_slice(&env, "No public bet found"),
+                market: MarketType::Winner,
             });
             res.active = active;
             res
@@ -264,6 +744,7 @@ pub fn get_PublicBet(env: Env, setting: i128) -> PublicBet {
             gameid: 0,
             active: false,
             description: String::from_slice(&env, "No public bet found"),
+            market: MarketType::Winner,
         });
     publicBet
 }
@@ -281,7 +762,10 @@ pub fn get_PrivateBet(env: Env, setting: i128) -> PrivateBet {
             )),
             description: String::from_slice(&env, "No private bet found"),
             amount_bet_min: 0,
+            amount_bet_max: 0,
+            fixed_stake: false,
             users_invated: Vec::new(&env),
+            market: MarketType::Winner,
         })
 }
 
@@ -349,6 +833,17 @@ pub fn add_UsersAmount(env: Env, game_setting: i128) {
         .set(&DataKey::AmountUsers(game_setting), &total);
 }
 
+pub fn sub_UsersAmount(env: Env, game_setting: i128) {
+    let amount: i128 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::AmountUsers(game_setting))
+        .unwrap_or(0);
+    env.storage()
+        .persistent()
+        .set(&DataKey::AmountUsers(game_setting), &(amount - 1));
+}
+
 pub fn UsersAmount(env: Env, game_setting: i128) -> bool {
     let amountX: i128 = env
         .storage()
@@ -387,9 +882,10 @@ pub fn verifySettingId(env: Env, SettingId: i128) {
             gameid: 0,
             active: false,
             description: String::from_slice(&env, "No public bet found"),
+            market: MarketType::Winner,
         });
     if publicBet.id != 0 {
-        panic!("Public setting with this ID already exists");
+        panic_with_error!(&env, BettingError::SettingIdAlreadyUsed);
     }
     let privateBet = env
         .storage()
@@ -405,10 +901,13 @@ pub fn verifySettingId(env: Env, SettingId: i128) {
             )),
             description: String::from_slice(&env, "No private bet found"),
             amount_bet_min: 0,
+            amount_bet_max: 0,
+            fixed_stake: false,
             users_invated: Vec::new(&env),
+            market: MarketType::Winner,
         });
     if privateBet.id != 0 {
-        panic!("Private setting with this ID already exists");
+        panic_with_error!(&env, BettingError::SettingIdAlreadyUsed);
     }
 }
 pub fn set_publicSetting(env: Env, publicBet: PublicBet) {
@@ -493,6 +992,9 @@ pub fn get_ResultGame(env: Env, game_id: i128) -> ResultGame {
             gameid: 0,
             description: String::from_slice(&env, "No result found"),
             result: BetKey::Team_local,
+            score: Score { local: 0, away: 0 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             distribution_executed: false,
         });
@@ -507,6 +1009,9 @@ pub fn puase_ResultGame(env: Env, game_id: i128, pause: bool) {
                 gameid: 0,
                 description: String::from_str(&env, ""),
                 result: BetKey::Team_local,
+                score: Score { local: 0, away: 0 },
+                half_time: HalfTime::NotReported,
+                outcomes: Map::new(&env),
                 pause: false,
                 distribution_executed: false,
             });
@@ -523,6 +1028,9 @@ pub fn distribution_ResultGame(env: Env, game_id: i128) {
                 gameid: 0,
                 description: String::from_str(&env, ""),
                 result: BetKey::Team_local,
+                score: Score { local: 0, away: 0 },
+                half_time: HalfTime::NotReported,
+                outcomes: Map::new(&env),
                 pause: false,
                 distribution_executed: false,
             });
@@ -537,18 +1045,11 @@ pub fn get_ListBetUser(env: Env, gameid: i128) -> Vec<(Address)> {
         .get(&DataKey::ListBetUser(gameid))
         .unwrap_or(Vec::new(&env))
 }
-pub fn get_Bet(env: Env, user: Address, setting: i128) -> Bet {
+pub fn get_Bets(env: Env, user: Address, setting: i128) -> Vec<Bet> {
     env.storage()
         .persistent()
         .get(&DataKey::Bet(user.clone(), setting))
-        .unwrap_or(Bet {
-            id: 0,
-            gameid: 0,
-            betType: BetType::Public,
-            Setting: 0,
-            bet: BetKey::Team_local,
-            amount_bet: 0,
-        })
+        .unwrap_or(Vec::new(&env))
 }
 
 // summitter
@@ -721,6 +1222,18 @@ pub fn add_total_bet(env: Env, game_id: i128, Amount: i128) {
         .set(&DataKey::TotalBet(game_id), &Amountx);
 }
 
+pub fn sub_total_bet(env: Env, game_id: i128, Amount: i128) {
+    let total_amount: i128 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::TotalBet(game_id))
+        .unwrap_or(0);
+    let Amountx = total_amount - Amount;
+    env.storage()
+        .persistent()
+        .set(&DataKey::TotalBet(game_id), &Amountx);
+}
+
 pub fn get_total_bet(env: Env, game_id: i128) -> i128 {
     let total_amount: i128 = env
         .storage()
@@ -729,7 +1242,7 @@ pub fn get_total_bet(env: Env, game_id: i128) -> i128 {
         .unwrap_or(0);
     total_amount
 }
-pub fn add_not_assesed_yet(env: Env, game_id: i128, Amount: i128, bet: BetKey) {
+pub fn add_not_assesed_yet(env: Env, game_id: i128, Amount: i128, bet: u32) {
     let total_amount: i128 = env
         .storage()
         .persistent()
@@ -740,7 +1253,7 @@ pub fn add_not_assesed_yet(env: Env, game_id: i128, Amount: i128, bet: BetKey) {
         .persistent()
         .set(&DataKey::NotAssesedYet(game_id, bet.clone()), &Amountx);
 }
-pub fn delete_not_assesed_yet(env: Env, game_id: i128, Amount: i128, bet: BetKey) {
+pub fn delete_not_assesed_yet(env: Env, game_id: i128, Amount: i128, bet: u32) {
     let total_amount: i128 = env
         .storage()
         .persistent()
@@ -751,7 +1264,7 @@ pub fn delete_not_assesed_yet(env: Env, game_id: i128, Amount: i128, bet: BetKey
         .persistent()
         .set(&DataKey::NotAssesedYet(game_id, bet.clone()), &Amountx);
 }
-pub fn get_not_assesed_yet(env: Env, game_id: i128, bet: BetKey) -> i128 {
+pub fn get_not_assesed_yet(env: Env, game_id: i128, bet: u32) -> i128 {
     let total_amount: i128 = env
         .storage()
         .persistent()
@@ -759,7 +1272,7 @@ pub fn get_not_assesed_yet(env: Env, game_id: i128, bet: BetKey) -> i128 {
         .unwrap_or(0);
     total_amount
 }
-pub fn add_approve_total(env: Env, game_id: i128, Amount: i128, bet: BetKey) {
+pub fn add_approve_total(env: Env, game_id: i128, Amount: i128, bet: u32) {
     let total_amount: i128 = env
         .storage()
         .persistent()
@@ -770,7 +1283,7 @@ pub fn add_approve_total(env: Env, game_id: i128, Amount: i128, bet: BetKey) {
         .persistent()
         .set(&DataKey::Approved(game_id, bet.clone()), &Amountx);
 }
-pub fn get_approve_total(env: Env, game_id: i128, bet: BetKey) -> i128 {
+pub fn get_approve_total(env: Env, game_id: i128, bet: u32) -> i128 {
     let total_amount: i128 = env
         .storage()
         .persistent()
@@ -779,7 +1292,7 @@ pub fn get_approve_total(env: Env, game_id: i128, bet: BetKey) -> i128 {
     total_amount
 }
 
-pub fn add_reject_total(env: Env, game_id: i128, Amount: i128, bet: BetKey) {
+pub fn add_reject_total(env: Env, game_id: i128, Amount: i128, bet: u32) {
     let total_amount: i128 = env
         .storage()
         .persistent()
@@ -790,7 +1303,7 @@ pub fn add_reject_total(env: Env, game_id: i128, Amount: i128, bet: BetKey) {
         .persistent()
         .set(&DataKey::Rejected(game_id, bet.clone()), &Amountx);
 }
-pub fn get_reject_total(env: Env, game_id: i128, bet: BetKey) -> i128 {
+pub fn get_reject_total(env: Env, game_id: i128, bet: u32) -> i128 {
     let total_amount: i128 = env
         .storage()
         .persistent()
@@ -868,3 +1381,136 @@ pub fn get_didUserWithdraw(env: Env, user: Address, game_id: i128) -> bool {
         .unwrap_or(false);
     didWithdraw
 }
+pub fn set_settingDistributed(env: Env, setting: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::SettingDistributed(setting), &true);
+}
+pub fn get_settingDistributed(env: Env, setting: i128) -> bool {
+    let distributed: bool = env
+        .storage()
+        .persistent()
+        .get(&DataKey::SettingDistributed(setting))
+        .unwrap_or(false);
+    distributed
+}
+pub fn set_gameCancelled(env: Env, game_id: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::GameCancelled(game_id), &true);
+}
+pub fn set_publisher(env: Env, key_id: u32, publisher: Publisher) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Publisher(key_id), &publisher);
+}
+pub fn get_publisher(env: Env, key_id: u32) -> Option<Publisher> {
+    env.storage().persistent().get(&DataKey::Publisher(key_id))
+}
+// assigns the next id of a kind
+pub fn next_id(env: Env, kind: IdKind) -> i128 {
+    let mut counters: IdCounters = env
+        .storage()
+        .instance()
+        .get(&COUNTER)
+        .unwrap_or(IdCounters {
+            setting: FIRST_SETTING_ID - 1,
+            bet: 0,
+            result: 0,
+            parlay: 0,
+        });
+    let id = match kind {
+        IdKind::Setting => {
+            counters.setting += 1;
+            counters.setting
+        }
+        IdKind::Bet => {
+            counters.bet += 1;
+            counters.bet
+        }
+        IdKind::Result => {
+            counters.result += 1;
+            counters.result
+        }
+        IdKind::Parlay => {
+            counters.parlay += 1;
+            counters.parlay
+        }
+    };
+    env.storage().instance().set(&COUNTER, &counters);
+    id
+}
+pub fn set_idOwner(env: Env, kind: IdKind, id: i128, owner: Address) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::IdOwner(kind, id), &owner);
+}
+pub fn get_idOwner(env: Env, kind: IdKind, id: i128) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::IdOwner(kind, id))
+}
+pub fn set_fixtureRoot(env: Env, root: BytesN<32>, key_id: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::FixtureRoot(root), &key_id);
+}
+pub fn get_fixtureRoot(env: Env, root: BytesN<32>) -> Option<u32> {
+    env.storage().persistent().get(&DataKey::FixtureRoot(root))
+}
+// the nonces are spent per signer, each one picks them on its own
+pub fn set_signatureNonce(env: Env, key_id: u32, nonce: u64) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::SignatureNonce(key_id, nonce), &true);
+}
+pub fn get_signatureNonce(env: Env, key_id: u32, nonce: u64) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SignatureNonce(key_id, nonce))
+        .unwrap_or(false)
+}
+pub fn get_gameCancelled(env: Env, game_id: i128) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GameCancelled(game_id))
+        .unwrap_or(false)
+}
+// parlay
+pub fn set_Parlay(env: Env, user: Address, parlay: Parlay) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Parlay(user, parlay.clone().id), &parlay);
+}
+pub fn get_Parlay(env: Env, user: Address, id: i128) -> Parlay {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Parlay(user, id))
+        .unwrap_or(Parlay {
+            id: 0,
+            legs: Vec::new(&env),
+            amount_bet: 0,
+            claimed: false,
+        })
+}
+pub fn get_ParlayPool(env: Env) -> i128 {
+    let amount: i128 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::ParlayPool)
+        .unwrap_or(0);
+    amount
+}
+pub fn set_ParlayPool(env: Env, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::ParlayPool, &amount);
+}
+// part of the parlay pool that backs the winnings of the open parlays
+pub fn get_ParlayReserved(env: Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&PARLAY_RESERVED)
+        .unwrap_or(0)
+}
+pub fn set_ParlayReserved(env: Env, amount: i128) {
+    env.storage().persistent().set(&PARLAY_RESERVED, &amount);
+}
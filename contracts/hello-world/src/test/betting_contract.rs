@@ -5,14 +5,22 @@ mod tests {
     use std::string::ToString;
 
     use super::*;
+    use crate::errors::BettingError;
     use crate::storage;
+    use crate::Constants::{
+        ADMIN_SIGNER, FIRST_SETTING_ID, PARAMS_TIMELOCK, ROTATION_TIMELOCK, SEED_COMMIT_BOND,
+        SIDE_AWAY, SIDE_LOCAL, SUPREME_COURT_WINDOW, TOTALS_OVER, TOTALS_UNDER, WINNER_AWAY, WINNER_DRAW, WINNER_LOCAL,
+    };
     use crate::types::{
-        AssessmentKey, Bet, BetKey, BetType, ClaimType, Game, PrivateBet, PublicBet, ResultGame,
+        AssessmentKey, Bet, BetKey, BetType, ClaimType, Game, GameCancellation, GameSchedule,
+        HalfTime, IdKind, MarketType, Parlay, ParlayLeg, PrivateBet, PublicBet, ResultGame, Score,
+        SelectionPolicy, Settlement, SignedEnvelope, Timeline, TimelineScope, UserKind,
     };
     use crate::{BettingContract, BettingContractClient};
     use alloc::vec::Vec;
+    use core::cell::Cell;
     use ed25519_dalek::{Keypair, Signer};
-    use rand::thread_rng;
+    use rand::{rngs::StdRng, SeedableRng};
     use soroban_sdk::xdr::ToXdr;
     use soroban_sdk::{FromVal, Vec as SorobanVec};
     use soroban_sdk::{symbol_short, token};
@@ -23,8 +31,8 @@ mod tests {
         },
         testutils::{Address as _, Ledger, LedgerInfo},
         xdr::WriteXdr,
-        Address, Bytes, BytesN, BytesN as _, InvokeError, String, Symbol, Symbol as _, TryIntoVal,
-        Val,
+        Address, Bytes, BytesN, BytesN as _, InvokeError, Map, String, Symbol, Symbol as _,
+        TryIntoVal, Val,
     };
     use token::Client as TokenClient;
     use token::StellarAssetClient as TokenAdminClient;
@@ -168,7 +176,7 @@ mod tests {
         env.mock_all_auths(); // Mock all authorizations for testing
 
         // Create mock accounts
-let adminPk = Keypair::generate(&mut thread_rng());
+let adminPk = Keypair::generate(&mut StdRng::seed_from_u64(1));
         let public_key = BytesN::<32>::from_array(&env, &adminPk.public.to_bytes());
 
 let admin = Address::generate(&env);
@@ -207,16 +215,70 @@ let admin = Address::generate(&env);
         )
     }
 
+    // the summiters of an activated game are drawn by a call in a later ledger than the activating bet
+    fn draw_game(client: &BettingContractClient, game_id: i128) {
+        client.env.ledger().set_sequence_number(client.env.ledger().sequence() + 1);
+        client.fallback_seed(&game_id);
+    }
+
+    // the summiters are drawn at random, returns both with the main summiter of the game last
+    fn drawn_summiters(
+        client: &BettingContractClient,
+        game_id: i128,
+        summiter: Address,
+        summiter2: Address,
+    ) -> (Address, Address) {
+        draw_game(client, game_id);
+        if client.get_game(&game_id).summiter == summiter {
+            (summiter2, summiter)
+        } else {
+            (summiter, summiter2)
+        }
+    }
+
     fn set_ledger_timestamp(env: &Env, timestamp: u32) {
         env.ledger().set(LedgerInfo {
             timestamp: timestamp as u64,
             protocol_version: 23, // Updated to match soroban-sdk 23.0.1
             sequence_number: env.ledger().sequence(),
+            network_id: env.ledger().network_id().to_array(),
             base_reserve: 10,
             ..Default::default()
         });
     }
 
+    // a node of a fixture Merkle tree, the sha256 of its two children in ascending order
+    fn merkle_parent(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+        let mut data = Bytes::new(env);
+        if a < b {
+            data.append(&Bytes::from(a.clone()));
+            data.append(&Bytes::from(b.clone()));
+        } else {
+            data.append(&Bytes::from(b.clone()));
+            data.append(&Bytes::from(a.clone()));
+        }
+        env.crypto().sha256(&data).to_bytes()
+    }
+
+    // every test runs on its own thread, so the nonces start from 1 in each test
+    std::thread_local! {
+        static NONCE: Cell<u64> = Cell::new(1);
+    }
+
+    // the message the admin signs: an envelope for this contract and network with a fresh nonce, then the payload
+    fn signed_message(env: &Env, client: &BettingContractClient, payload: Bytes) -> (SignedEnvelope, Vec<u8>) {
+        let envelope = SignedEnvelope {
+            version: 1,
+            contract: client.address.clone(),
+            network_id: env.ledger().network_id(),
+            nonce: NONCE.with(|nonce| nonce.replace(nonce.get() + 1)),
+            expiry_ledger: env.ledger().sequence() + 1000,
+        };
+        let mut message = envelope.clone().to_xdr(env);
+        message.append(&payload);
+        (envelope, message.iter().collect())
+    }
+
     #[test]
     fn test_request_result_summiter() {
         let (env, client, admin,key,pk, user, token_usd, token_trust, _, _, _, _) = create_test_env();
@@ -260,19 +322,19 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex);
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
 
         // Place a public bet
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -309,20 +371,20 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
 
         set_ledger_timestamp(&env, 1500);
 
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -352,18 +414,18 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex );
 
         // Place a public bet
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -403,12 +465,12 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex );
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -421,7 +483,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -434,19 +496,23 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -479,12 +545,12 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
 
         set_ledger_timestamp(&env, 2500);
 
@@ -492,6 +558,9 @@ let admin = Address::generate(&env);
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_slice(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -521,12 +590,12 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex,);
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex,);
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -539,7 +608,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -552,19 +621,23 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -607,12 +680,12 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex,);
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex,);
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -626,7 +699,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -641,19 +714,23 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Cancel,
+            score: Score { local: 0, away: 0 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -662,9 +739,10 @@ let admin = Address::generate(&env);
         client.summitResult(&summiter2, &result);
 
         client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
 
-        // Execute distribution
-        //client.execute_distribution(&game_id);
+        // Execute distribution, the cancelled game gives the summiters their stakes back
+        client.execute_distribution(&game_id, &game_id);
         // Claim as winner
 
         //client.claim(&user, &ClaimType::User, &game_id);
@@ -687,19 +765,19 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encodedx: Vec<u8> = gamex.clone().to_xdr(&env).iter().collect();
+        let (envelopex, encodedx) = signed_message(&env, &client, gamex.clone().to_xdr(&env));
 
 
 
         let signaturex2: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encodedx.as_slice()).to_bytes());
-        client.set_game(&gamex, &signaturex2, );
+        client.set_game(&gamex, &ADMIN_SIGNER, &envelopex, &signaturex2, );
 
         //let's bet to active the game
         let betxz = Bet {
             id: 122,
             Setting: game_idx,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_idx,
@@ -709,18 +787,23 @@ let admin = Address::generate(&env);
         let betx2 = Bet {
             id: 222,
             Setting: game_idx,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_idx,
         };
         client.bet(&user2, &betx2);
+        // the same summiters are drawn again for the next game
+        let (summiter2, summiter) = drawn_summiters(&client, game_idx, summiter, summiter2);
         set_ledger_timestamp(&env, 4002);
 
         let result = ResultGame {
             id: 1,
             gameid: game_idx,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -734,13 +817,14 @@ let admin = Address::generate(&env);
         client.summitResult(&summiter, &result);
 
         client.assessResult(&user, &game_idx, &game_idx, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_idx, &game_idx, &AssessmentKey::approve);
 
         // Execute distribution
-        //client.execute_distribution(&game_idx);
+        client.execute_distribution(&game_idx, &game_idx);
         // Claim as winner
 
         client.claim(&user, &ClaimType::User, &game_idx);
-        client.claim(&admin, &ClaimType::Summiter, &game_idx);
+        client.claim(&summiter, &ClaimType::Summiter, &game_idx);
     }
     #[test]
     fn test_adm_summitter() {
@@ -768,20 +852,20 @@ let admin = Address::generate(&env);
             endTime: 2000,
             summiter: Address::generate(&env),
             Checker: soroban_sdk::Vec::new(&env),
-            active: true,
+            active: false,
             league: 1,
             description: String::from_slice(&env, "Team A vs Team B"),
             team_local: 33,
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex);
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -795,7 +879,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -810,43 +894,21 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
-        // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
-
-        let result = ResultGame {
-            id: 1,
-            gameid: game_id,
-            result: BetKey::Team_local,
-            pause: false,
-            description: String::from_str(&env, "Final Score 2-1"),
-            distribution_executed: false,
-        };
-
-        client.summitResult(&summiter2, &result);
-
-        client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
-
-        // Execute distribution
-        //client.execute_distribution(&game_id);
-        // Claim as winner
-
-        client.claim(&user, &ClaimType::User, &game_id);
-        client.claim(&summiter2, &ClaimType::Summiter, &game_id);
-
+        let (summiter2, summiter) = drawn_summiters(&client, game_id, summiter, summiter2);
         //Repaet Process
 
         // Set up a game
         let game_idx = 31;
         let gamex = Game {
             id: game_idx,
-            startTime: 3000,
-            endTime: 4000,
+            startTime: 7000,
+            endTime: 8000,
             summiter: Address::generate(&env),
             Checker: soroban_sdk::Vec::new(&env),
             active: false,
@@ -856,19 +918,19 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encodedx: Vec<u8> = gamex.clone().to_xdr(&env).iter().collect();
+        let (envelopex, encodedx) = signed_message(&env, &client, gamex.clone().to_xdr(&env));
 
 
 
         let signaturex2: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encodedx.as_slice()).to_bytes());
-        client.set_game(&gamex, &signaturex2);
+        client.set_game(&gamex, &ADMIN_SIGNER, &envelopex, &signaturex2);
 
         //let's bet to active the game
         let betxz = Bet {
             id: 122,
             Setting: game_idx,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_idx,
@@ -878,18 +940,52 @@ let admin = Address::generate(&env);
         let betx2 = Bet {
             id: 222,
             Setting: game_idx,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_idx,
         };
         client.bet(&user2, &betx2);
-        set_ledger_timestamp(&env, 4002);
+        // both stakes are still locked by the first game, the admin is drawn
+        assert_eq!(client.get_game(&game_idx).summiter, admin);
+
+        // Set ledger timestamp after game end
+        set_ledger_timestamp(&env, 2100);
+
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+
+        client.summitResult(&summiter, &result);
+
+        client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&summiter2, &0, &game_id, &AssessmentKey::approve);
+
+        // Execute distribution
+        client.execute_distribution(&game_id, &game_id);
+        // Claim as winner
+
+        client.claim(&user, &ClaimType::User, &game_id);
+        client.claim(&summiter2, &ClaimType::Summiter, &game_id);
+
+        set_ledger_timestamp(&env, 8002);
 
         let result = ResultGame {
             id: 1,
             gameid: game_idx,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -903,9 +999,10 @@ let admin = Address::generate(&env);
         client.summitResult(&admin, &result);
 
         client.assessResult(&user, &game_idx, &game_idx, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_idx, &game_idx, &AssessmentKey::approve);
 
         // Execute distribution
-        //client.execute_distribution(&game_idx);
+        client.execute_distribution(&game_idx, &game_idx);
         // Claim as winner
 
         client.claim(&user, &ClaimType::User, &game_idx);
@@ -944,13 +1041,13 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex);
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -964,7 +1061,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -979,19 +1076,23 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        let (summiter2, summiter) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1000,12 +1101,14 @@ let admin = Address::generate(&env);
         client.summitResult(&summiter, &result);
 
         client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&summiter2, &0, &game_id, &AssessmentKey::approve);
 
-        // Execute distribution&¿
-        //client.execute_distribution(&game_id);
+        // Execute distribution
+        client.execute_distribution(&game_id, &game_id);
         // Claim as winner
 
-        client.claim(&user, &ClaimType::User, &112);
+        client.claim(&user, &ClaimType::User, &game_id);
         client.claim(&summiter2, &ClaimType::Summiter, &game_id);
 
         // Verify token transfers (winner gets bet + share of pool)
@@ -1045,13 +1148,13 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -1065,7 +1168,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -1080,12 +1183,13 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        draw_game(&client, game_id);
         // Set ledger timestamp after game end plus 3 hours
         set_ledger_timestamp(&env, 12810);
 
@@ -1140,12 +1244,12 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -1159,7 +1263,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -1174,19 +1278,23 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Cancel,
+            score: Score { local: 0, away: 0 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1195,9 +1303,10 @@ let admin = Address::generate(&env);
         client.summitResult(&summiter2, &result);
 
         client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
 
         // Execute distribution
-        //client.execute_distribution(&game_id);
+        client.execute_distribution(&game_id, &game_id);
         // Claim as winner
 
         client.claim_refund(&user, &game_id);
@@ -1253,12 +1362,12 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -1271,7 +1380,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -1284,30 +1393,37 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        // the draw in the ledger after the activation published the summiters
+        draw_game(&client, game_id);
+        let events = env.events().all();
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
-        struct SummitersSeletedEvent {
-            game_id: i128,
-            main: Address,
-            summiters: Vec<Address>,
-        }
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
         };
-        let events = env.events().all();
-        let (_, _, value) = events.get(0).unwrap();
+        let (_, _, value) = events
+            .iter()
+            .find(|(_, topics, _)| {
+                let topic: Option<Symbol> =
+                    topics.get(1).and_then(|topic| topic.try_into_val(&env).ok());
+                topic == Some(Symbol::new(&env, "Seleted_Suimmiters"))
+            })
+            .unwrap();
         let raw: SorobanVec<Val> = value.try_into_val(&env).unwrap();
 
         let game_id: i128 = raw.get(0).unwrap().try_into_val(&env).unwrap();
@@ -1320,10 +1436,12 @@ let admin = Address::generate(&env);
             std::println!("Summiter: {:?}", s);
         }
 
-        client.summitResult(&summiter2, &result);
+        client.summitResult(&main, &result);
 
         client.assessResult(&user, &game_id, &game_id, &AssessmentKey::reject);
-        //client.execute_distribution(&game_id);
+        // the rejected result waits for the supreme court
+        set_ledger_timestamp(&env, 20500);
+        client.execute_distribution(&game_id, &game_id);
         std::println!("User balance final {:?}", token_usd_client.balance(&user));
         std::println!("User2 balance final {:?}", token_usd_client.balance(&user2));
         std::println!(
@@ -1369,14 +1487,14 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
-        let signer1 = Keypair::generate(&mut thread_rng());
+        let signer1 = Keypair::generate(&mut StdRng::seed_from_u64(2));
         let public_key = BytesN::<32>::from_array(&env, &signer1.public.to_bytes());
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -1389,7 +1507,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -1402,19 +1520,23 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1423,6 +1545,9 @@ let admin = Address::generate(&env);
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1432,6 +1557,8 @@ let admin = Address::generate(&env);
         client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
         client.assessResult(&user, &game_id, &game_id, &AssessmentKey::reject);
 
+        // the supreme court decides once the assessment is over
+        set_ledger_timestamp(&env, 20500);
         client.setResult_supremCourt(&result2);
 
         client.claim(&user2, &ClaimType::User, &game_id);
@@ -1482,14 +1609,14 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
-        let signer1 = Keypair::generate(&mut thread_rng());
+        let signer1 = Keypair::generate(&mut StdRng::seed_from_u64(2));
         let public_key = BytesN::<32>::from_array(&env, &signer1.public.to_bytes());
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -1502,7 +1629,7 @@ let admin = Address::generate(&env);
         let bet = Bet {
             id: 1,
             Setting: game_id,
-            bet: BetKey::Team_away,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
@@ -1517,19 +1644,23 @@ let admin = Address::generate(&env);
         let betx = Bet {
             id: 2,
             Setting: game_id,
-            bet: BetKey::Team_local,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Public,
             gameid: game_id,
         };
         client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1538,6 +1669,9 @@ let admin = Address::generate(&env);
             id: 1,
             gameid: game_id,
             result: BetKey::Team_away,
+            score: Score { local: 1, away: 2 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1547,6 +1681,8 @@ let admin = Address::generate(&env);
         client.assessResult(&user, &game_id, &game_id, &AssessmentKey::reject);
         client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::reject);
         client.assessResult(&summiter, &0, &game_id, &AssessmentKey::approve);
+        // the supreme court decides once the assessment is over
+        set_ledger_timestamp(&env, 20500);
         client.setResult_supremCourt(&result2);
         // Execute distribution
 
@@ -1602,14 +1738,14 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
-        let signer1 = Keypair::generate(&mut thread_rng());
+        let signer1 = Keypair::generate(&mut StdRng::seed_from_u64(2));
         let public_key = BytesN::<32>::from_array(&env, &signer1.public.to_bytes());
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
         //add the user who wna tto participate as a summiter
         let summiter = Address::generate(&env);
         let summiter2 = Address::generate(&env);
@@ -1620,20 +1756,23 @@ let admin = Address::generate(&env);
         let user2 = Address::generate(&env);
 
         let privateSetting = PrivateBet {
-            id: 11,
+            id: 0,
             gameid: game_id,
             active: false,
             settingAdmin: user2.clone(),
             description: String::from_str(&env, "Private Bet 1"),
             amount_bet_min: 500,
+            amount_bet_max: 0,
+            fixed_stake: false,
             users_invated: vec![&env, user.clone(), user2.clone()],
+            market: MarketType::Winner,
         };
-        client.set_private_bet(&user2, &privateSetting, &game_id);
+        let setting = client.set_private_bet(&user2, &privateSetting, &game_id);
         //let's bet to active the game
         let bet = Bet {
             id: 1,
-            Setting: 11,
-            bet: BetKey::Team_away,
+            Setting: setting,
+            bet: WINNER_AWAY,
             amount_bet: 1000,
             betType: BetType::Private,
             gameid: game_id,
@@ -1646,8 +1785,8 @@ let admin = Address::generate(&env);
 
         let betx = Bet {
             id: 2,
-            Setting: 11,
-            bet: BetKey::Team_local,
+            Setting: setting,
+            bet: WINNER_LOCAL,
             amount_bet: 1000,
             betType: BetType::Private,
             gameid: game_id,
@@ -1655,13 +1794,17 @@ let admin = Address::generate(&env);
         let initial_usd_balance2 = token_usd_client.balance(&user2);
         let initial_trust_balance2 = token_trust_client.balance(&user2);
         client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 2100);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Team_away,
+            score: Score { local: 1, away: 2 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1669,17 +1812,17 @@ let admin = Address::generate(&env);
 
         client.summitResult(&summiter2, &result);
 
-        client.assessResult(&user2, &11, &game_id, &AssessmentKey::approve);
-        client.assessResult(&user, &11, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &setting, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user, &setting, &game_id, &AssessmentKey::approve);
         client.assessResult(&summiter, &0, &game_id, &AssessmentKey::approve);
 
         //client.assessResult(&user, &bet, &game_id, &AssessmentKey::approve);
-        client.execute_distribution(&game_id, &11);
+        client.execute_distribution(&game_id, &setting);
         //client.setResult_supremCourt(&user, &result2);
         // Execute distribution
 
-        client.claim(&user, &ClaimType::User, &11);
-        client.claim(&user2, &ClaimType::User, &11);
+        client.claim(&user, &ClaimType::User, &setting);
+        client.claim(&user2, &ClaimType::User, &setting);
         client.claim(&summiter, &ClaimType::Summiter, &game_id);
         client.claim(&summiter2, &ClaimType::Summiter, &game_id);
         client.claim(&admin, &ClaimType::Protocol, &game_id);
@@ -1704,6 +1847,155 @@ let admin = Address::generate(&env);
         assert_eq!(token_trust_client.balance(&user2), initial_trust_balance2); // Trust tokens returned
     }
     #[test]
+    fn test_private_proportional_stakes() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+        let user2 = Address::generate(&env);
+        let user3 = Address::generate(&env);
+        for friend in [user2.clone(), user3.clone()] {
+            adm_usd.mint(&friend, &100_000_000);
+            adm_trust.mint(&friend, &100_000_000);
+        }
+
+        // users can bet any amount between 100 and 2000
+        let privateSetting = PrivateBet {
+            id: 0,
+            gameid: game_id,
+            active: false,
+            settingAdmin: user2.clone(),
+            description: String::from_str(&env, "Private Bet 1"),
+            amount_bet_min: 100,
+            amount_bet_max: 2000,
+            fixed_stake: false,
+            users_invated: vec![&env, user.clone(), user2.clone(), user3.clone()],
+            market: MarketType::Winner,
+        };
+        let setting = client.set_private_bet(&user2, &privateSetting, &game_id);
+        for (id, bettor, outcome, amount) in [
+            (1, user.clone(), WINNER_LOCAL, 1000),
+            (2, user2.clone(), WINNER_AWAY, 500),
+            (3, user3.clone(), WINNER_LOCAL, 300),
+        ] {
+            let bet = Bet {
+                id,
+                Setting: setting,
+                bet: outcome,
+                amount_bet: amount,
+                betType: BetType::Private,
+                gameid: game_id,
+            };
+            client.bet(&bettor, &bet);
+        }
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+        set_ledger_timestamp(&env, 2100);
+
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &setting, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &setting, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user3, &setting, &game_id, &AssessmentKey::approve);
+        client.execute_distribution(&game_id, &setting);
+
+        // the winners share the pool by their stake, 1000 and 300 of the 1300 winner pool
+        let pool = client.get_pools(&setting).pool;
+        assert_eq!(client.preview_claim(&user, &setting).amountUsd, 1000 + (1000 * pool) / 1300);
+        assert_eq!(client.preview_claim(&user3, &setting).amountUsd, 300 + (300 * pool) / 1300);
+    }
+    #[test]
+    #[should_panic(expected = "Error(Contract, #228)")]
+    fn test_private_stake_above_max() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, _, _) =
+            create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let privateSetting = PrivateBet {
+            id: 0,
+            gameid: game_id,
+            active: false,
+            settingAdmin: user.clone(),
+            description: String::from_str(&env, "Private Bet 1"),
+            amount_bet_min: 100,
+            amount_bet_max: 2000,
+            fixed_stake: false,
+            users_invated: vec![&env, user.clone()],
+            market: MarketType::Winner,
+        };
+        let setting = client.set_private_bet(&user, &privateSetting, &game_id);
+        let bet = Bet {
+            id: 1,
+            Setting: setting,
+            bet: WINNER_LOCAL,
+            amount_bet: 2500,
+            betType: BetType::Private,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet); // Should panic, the setting only takes up to 2000
+    }
+    #[test]
     fn test_set_private_x() {
         let (
             env,
@@ -1738,14 +2030,14 @@ let admin = Address::generate(&env);
             team_away: 44,
         };
         // Encode game to Bytes (variable length)
-        let encoded: Vec<u8> = game.clone().to_xdr(&env).iter().collect();
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
 
-        let signer1 = Keypair::generate(&mut thread_rng());
+        let signer1 = Keypair::generate(&mut StdRng::seed_from_u64(2));
         let public_key = BytesN::<32>::from_array(&env, &signer1.public.to_bytes());
 
         let signaturex: BytesN<64> =
             BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
-        client.set_game(&game, &signaturex, );
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex, );
         all_events.push(env.events().all());
 
         //add the user who wna tto participate as a summiter
@@ -1768,22 +2060,25 @@ let admin = Address::generate(&env);
         let user2 = Address::generate(&env);
 
         let privateSetting = PrivateBet {
-            id: 11,
+            id: 0,
             gameid: game_id,
             active: false,
             settingAdmin: user2.clone(),
             description: String::from_str(&env, "Private Bet 1"),
             amount_bet_min: 500,
+            amount_bet_max: 0,
+            fixed_stake: false,
             users_invated: vec![&env, user.clone(), user2.clone()],
+            market: MarketType::Winner,
         };
-        client.set_private_bet(&user2, &privateSetting, &game_id);
+        let setting = client.set_private_bet(&user2, &privateSetting, &game_id);
         all_events.push(env.events().all());
 
         //let's bet to active the game
         let bet = Bet {
             id: 1,
-            Setting: 11,
-            bet: BetKey::Team_away,
+            Setting: setting,
+            bet: WINNER_AWAY,
             amount_bet: 500,
             betType: BetType::Private,
             gameid: game_id,
@@ -1800,8 +2095,8 @@ let admin = Address::generate(&env);
 
         let betx = Bet {
             id: 2,
-            Setting: 11,
-            bet: BetKey::Team_local,
+            Setting: setting,
+            bet: WINNER_LOCAL,
             amount_bet: 500,
             betType: BetType::Private,
             gameid: game_id,
@@ -1812,13 +2107,17 @@ let admin = Address::generate(&env);
         client.bet(&user2, &betx);
         all_events.push(env.events().all());
 
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
         // Set ledger timestamp after game end
-        set_ledger_timestamp(&env, 2500);
+        set_ledger_timestamp(&env, 6000);
 
         let result = ResultGame {
             id: 1,
             gameid: game_id,
             result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1827,6 +2126,9 @@ let admin = Address::generate(&env);
             id: 1,
             gameid: game_id,
             result: BetKey::Team_away,
+            score: Score { local: 1, away: 2 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
             pause: false,
             description: String::from_str(&env, "Final Score 2-1"),
             distribution_executed: false,
@@ -1834,10 +2136,10 @@ let admin = Address::generate(&env);
         client.summitResult(&summiter, &result);
         all_events.push(env.events().all());
 
-        client.assessResult(&user, &11, &game_id, &AssessmentKey::reject);
+        client.assessResult(&user, &setting, &game_id, &AssessmentKey::reject);
         all_events.push(env.events().all());
 
-                client.assessResult(&user2, &11, &game_id, &AssessmentKey::reject);
+                client.assessResult(&user2, &setting, &game_id, &AssessmentKey::reject);
         all_events.push(env.events().all());
         client.assessResult(&summiter, &0, &game_id, &AssessmentKey::reject);
                 set_ledger_timestamp(&env, 20500);
@@ -1847,10 +2149,10 @@ let admin = Address::generate(&env);
 
         // Execute distribution
 
-        client.claim(&user, &ClaimType::User, &11);
+        client.claim(&user, &ClaimType::User, &setting);
         all_events.push(env.events().all());
 
-        //client.claim(&user2, &ClaimType::User, &11);
+        //client.claim(&user2, &ClaimType::User, &setting);
         client.claim(&summiter, &ClaimType::Summiter, &game_id);
         client.claim(&summiter2, &ClaimType::Summiter, &game_id);
         client.claim(&admin, &ClaimType::Protocol, &game_id);
@@ -1875,4 +2177,3589 @@ let admin = Address::generate(&env);
         assert!(token_usd_client.balance(&user) > initial_usd_balance);
         assert_eq!(token_trust_client.balance(&user), initial_trust_balance); // Trust tokens returned
     }
+    #[test]
+    fn test_public_setting_distribution() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        // Set up a game, the public setting is created with the game id
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        let initial_usd_balance = token_usd_client.balance(&user);
+        let initial_trust_balance = token_trust_client.balance(&user);
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let initial_trust_balance2 = token_trust_client.balance(&user2);
+
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+        set_ledger_timestamp(&env, 2100);
+
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+
+        client.execute_distribution(&game_id, &game_id);
+
+        client.claim(&user, &ClaimType::User, &game_id);
+        client.claim(&user2, &ClaimType::User, &game_id);
+
+        // winner gets his bet back plus the pool after the protocol and summiter cut
+        assert_eq!(token_usd_client.balance(&user), initial_usd_balance + 1400);
+        assert_eq!(token_trust_client.balance(&user), initial_trust_balance);
+        assert_eq!(token_trust_client.balance(&user2), initial_trust_balance2);
+    }
+    #[test]
+    fn test_totals_setting_distribution() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        // over/under 2.5 goals
+        let totalsSetting = PublicBet {
+            id: 12,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Over/Under 2.5"),
+            market: MarketType::Totals(250),
+        };
+        let setting = client.set_public_bet(&admin, &totalsSetting, &game_id);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: setting,
+            bet: TOTALS_OVER,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        let initial_usd_balance = token_usd_client.balance(&user);
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let initial_usd_balance2 = token_usd_client.balance(&user2);
+
+        let betx = Bet {
+            id: 2,
+            Setting: setting,
+            bet: TOTALS_UNDER,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+        set_ledger_timestamp(&env, 2100);
+
+        // 2-1 is three goals so the over wins even if the local team won
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &setting, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &setting, &game_id, &AssessmentKey::approve);
+
+        client.execute_distribution(&game_id, &setting);
+
+        client.claim(&user, &ClaimType::User, &setting);
+
+        assert_eq!(token_usd_client.balance(&user), initial_usd_balance + 1400);
+        assert_eq!(token_usd_client.balance(&user2), initial_usd_balance2 - 1000);
+    }
+    #[test]
+    fn test_summiters_judged_once_per_game() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        let totalsSetting = PublicBet {
+            id: 0,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Over/Under 2.5"),
+            market: MarketType::Totals(250),
+        };
+        let totals = client.set_public_bet(&admin, &totalsSetting, &game_id);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        // two bettors on the winner of the game and two on the goals
+        let mut bettors: Vec<(Address, i128, u32)> = Vec::new();
+        bettors.push((user.clone(), game_id, WINNER_LOCAL));
+        for (setting, outcome) in
+            [(game_id, WINNER_AWAY), (totals, TOTALS_OVER), (totals, TOTALS_UNDER)]
+        {
+            let bettor = Address::generate(&env);
+            adm_usd.mint(&bettor, &100_000_000);
+            adm_trust.mint(&bettor, &100_000_000);
+            bettors.push((bettor, setting, outcome));
+        }
+        for (id, (bettor, setting, outcome)) in bettors.iter().enumerate() {
+            let bet = Bet {
+                id: id as i128 + 1,
+                Setting: *setting,
+                bet: *outcome,
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            };
+            client.bet(bettor, &bet);
+        }
+        let (checker, main) = drawn_summiters(&client, game_id, summiter, summiter2);
+        let history = || {
+            env.as_contract(&client.address, || {
+                storage::get_history(env.clone(), checker.clone())
+            })
+        };
+        let before = history();
+
+        set_ledger_timestamp(&env, 2100);
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&main, &result);
+        client.assessResult(&checker, &0, &game_id, &AssessmentKey::approve);
+        for (bettor, setting, _) in bettors.iter() {
+            client.assessResult(bettor, setting, &game_id, &AssessmentKey::approve);
+        }
+
+        // the checker is rewarded by the first setting distributed only
+        client.execute_distribution(&game_id, &game_id);
+        let points = client.get_game_params(&game_id).summiter_points;
+        assert_eq!(history(), before + points);
+        client.execute_distribution(&game_id, &totals);
+        assert_eq!(history(), before + points);
+    }
+    #[test]
+    fn test_one_vote_counts_in_every_setting() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        let totalsSetting = PublicBet {
+            id: 0,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Over/Under 2.5"),
+            market: MarketType::Totals(250),
+        };
+        let totals = client.set_public_bet(&admin, &totalsSetting, &game_id);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        // the user holds a position in both settings of the game, each against another bettor
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let positions = [
+            (user.clone(), game_id, WINNER_LOCAL),
+            (user.clone(), totals, TOTALS_OVER),
+            (user2.clone(), game_id, WINNER_AWAY),
+            (user2.clone(), totals, TOTALS_UNDER),
+        ];
+        for (id, (bettor, setting, outcome)) in positions.iter().enumerate() {
+            let bet = Bet {
+                id: id as i128 + 1,
+                Setting: *setting,
+                bet: *outcome,
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            };
+            client.bet(bettor, &bet);
+        }
+        let (checker, main) = drawn_summiters(&client, game_id, summiter, summiter2);
+
+        set_ledger_timestamp(&env, 2100);
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&main, &result);
+        client.assessResult(&checker, &0, &game_id, &AssessmentKey::approve);
+
+        // a single vote of each user is counted in both settings
+        client.assessResult(&user, &totals, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+        assert!(client
+            .try_assessResult(&user, &game_id, &game_id, &AssessmentKey::approve)
+            .is_err());
+        env.as_contract(&client.address, || {
+            for (setting, outcome) in [(game_id, WINNER_LOCAL), (totals, TOTALS_OVER)] {
+                assert!(storage::UsersAmount(env.clone(), setting));
+                assert_eq!(storage::get_not_assesed_yet(env.clone(), setting, outcome), 0);
+                assert_eq!(storage::get_approve_total(env.clone(), setting, outcome), 1000);
+            }
+        });
+
+        let initial_usd_balance = token_usd_client.balance(&user);
+        client.execute_distribution(&game_id, &game_id);
+        client.execute_distribution(&game_id, &totals);
+        client.claim(&user, &ClaimType::User, &game_id);
+        let after_winner = token_usd_client.balance(&user);
+        assert!(after_winner > initial_usd_balance);
+        client.claim(&user, &ClaimType::User, &totals);
+        assert_eq!(
+            token_usd_client.balance(&user) - after_winner,
+            after_winner - initial_usd_balance
+        );
+    }
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_totals_setting_wrong_outcome() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, _, _) =
+            create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let totalsSetting = PublicBet {
+            id: 12,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Over/Under 2.5"),
+            market: MarketType::Totals(250),
+        };
+        let setting = client.set_public_bet(&admin, &totalsSetting, &game_id);
+
+        let bet = Bet {
+            id: 1,
+            Setting: setting,
+            bet: 2,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet); // Should panic, the totals market only has two outcomes
+    }
+    #[test]
+    fn test_quarter_handicap_settlement() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        // local team -0.25, half the stake on -0.5 and half on level ball
+        let handicapSetting = PublicBet {
+            id: 12,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Local -0.25"),
+            market: MarketType::Handicap(-25),
+        };
+        let setting = client.set_public_bet(&admin, &handicapSetting, &game_id);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: setting,
+            bet: SIDE_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: setting,
+            bet: SIDE_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+        set_ledger_timestamp(&env, 2100);
+
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Draw,
+            score: Score { local: 1, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 1-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &setting, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &setting, &game_id, &AssessmentKey::approve);
+        client.execute_distribution(&game_id, &setting);
+
+        // the draw loses half of the local stake and refunds the other half
+        let preview = client.preview_claim(&user, &setting);
+        assert_eq!(preview.settlement, Settlement::HalfLoss);
+        assert_eq!(preview.amountUsd, 500);
+        assert_eq!(preview.amountTrust, 300);
+
+        // the away bettor gets his stake back and wins the lost half
+        let preview2 = client.preview_claim(&user2, &setting);
+        assert_eq!(preview2.settlement, Settlement::HalfWin);
+        assert_eq!(preview2.amountUsd, 500 + 500 + 1050);
+        assert_eq!(
+            client.claim(&user2, &ClaimType::User, &setting),
+            (preview2.amountUsd, preview2.amountTrust)
+        );
+    }
+    #[test]
+    fn test_bet_positions_and_top_up() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let winnerSetting = PublicBet {
+            id: 13,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Winner"),
+            market: MarketType::Winner,
+        };
+        let setting = client.set_public_bet(&admin, &winnerSetting, &game_id);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        // the user tops up his local position and hedges on the away team
+        for (id, outcome, amount) in [
+            (1, WINNER_LOCAL, 1000),
+            (2, WINNER_LOCAL, 500),
+            (3, WINNER_AWAY, 300),
+        ] {
+            let bet = Bet {
+                id,
+                Setting: setting,
+                bet: outcome,
+                amount_bet: amount,
+                betType: BetType::Public,
+                gameid: game_id,
+            };
+            client.bet(&user, &bet);
+        }
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 4,
+            Setting: setting,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+
+        let positions = client.get_bet(&user, &setting);
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions.get(0).unwrap().amount_bet, 1500);
+        assert_eq!(positions.get(1).unwrap().amount_bet, 300);
+        // the points are charged once per setting
+        assert_eq!(client.get_honesty_points(&user), -20);
+        set_ledger_timestamp(&env, 2100);
+
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &setting, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &setting, &game_id, &AssessmentKey::approve);
+        client.execute_distribution(&game_id, &setting);
+
+        // the lost hedge goes to the pool and the local position takes all of it
+        let pools = client.get_pools(&setting);
+        assert_eq!(pools.winnerPool, 1500);
+        let preview = client.preview_claim(&user, &setting);
+        assert_eq!(preview.kind, UserKind::WinnerHonest);
+        assert_eq!(preview.settlement, Settlement::Win);
+        assert_eq!(preview.amountUsd, 1500 + pools.pool);
+        assert_eq!(preview.amountTrust, (1800 * 30) / 100);
+        assert_eq!(
+            client.claim(&user, &ClaimType::User, &setting),
+            (preview.amountUsd, preview.amountTrust)
+        );
+        assert_eq!(client.preview_claim(&user2, &setting).kind, UserKind::LoserHonest);
+    }
+    #[test]
+    fn test_cancel_and_update_bet() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let winnerSetting = PublicBet {
+            id: 13,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Winner"),
+            market: MarketType::Winner,
+        };
+        let setting = client.set_public_bet(&admin, &winnerSetting, &game_id);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: setting,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: setting,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        assert!(client.get_public_setting(&setting).active);
+
+        // moving the only away stake to the local team leaves a single side
+        client.update_bet(&user2, &setting, &WINNER_AWAY, &WINNER_LOCAL);
+        assert!(!client.get_public_setting(&setting).active);
+        assert_eq!(client.get_bet(&user2, &setting).get(0).unwrap().bet, WINNER_LOCAL);
+        client.update_bet(&user2, &setting, &WINNER_LOCAL, &WINNER_DRAW);
+        assert!(client.get_public_setting(&setting).active);
+
+        // the user leaves with his stake minus the fee, his trust tokens and his points
+        let usd_balance = token_usd_client.balance(&user);
+        let trust_balance = token_trust_client.balance(&user);
+        assert_eq!(client.cancel_bet(&user, &setting, &WINNER_LOCAL), 980);
+        assert_eq!(token_usd_client.balance(&user), usd_balance + 980);
+        assert_eq!(token_trust_client.balance(&user), trust_balance + 300);
+        assert_eq!(client.get_honesty_points(&user), 0);
+        assert!(!client.get_public_setting(&setting).active);
+        assert_eq!(client.claim(&admin, &ClaimType::Protocol, &0), (20, 0));
+
+        // once the game has started the positions are locked
+        set_ledger_timestamp(&env, 1100);
+        assert!(client.try_cancel_bet(&user2, &setting, &WINNER_DRAW).is_err());
+    }
+    #[test]
+    fn test_cancel_bet_releases_summiters() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        let (checker, main) = drawn_summiters(&client, game_id, summiter, summiter2);
+        assert_eq!(client.get_stake(&main), 0);
+        assert_eq!(client.get_stake(&checker), 0);
+
+        // the last opposing position leaves, no setting keeps the game active
+        client.cancel_bet(&user2, &game_id, &WINNER_AWAY);
+        let game = client.get_game(&game_id);
+        assert!(!game.active);
+        assert_eq!(game.summiter, admin);
+        assert!(game.Checker.is_empty());
+        for released in [main.clone(), checker.clone()] {
+            assert_eq!(client.get_stake(&released), 1000);
+            assert!(client.get_summiter_bond(&released).games.is_empty());
+        }
+        assert!(!client.get_selection_draw(&game_id).drawn);
+
+        // a new opposing position activates the game and draws again
+        client.bet(&user2, &betx);
+        draw_game(&client, game_id);
+        let game = client.get_game(&game_id);
+        assert!(game.active);
+        assert!(client.get_selection_draw(&game_id).drawn);
+    }
+    #[test]
+    fn test_cancel_game_refunds() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let usd_balance = token_usd_client.balance(&user);
+        let trust_balance = token_trust_client.balance(&user);
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        draw_game(&client, game_id);
+        // the selected summiters lock their stake for the game
+        assert_eq!(client.get_stake(&summiter), 0);
+        assert_eq!(client.get_stake(&summiter2), 0);
+
+        let cancellation = GameCancellation {
+            game_id,
+            reason: String::from_str(&env, "Postponed by the league"),
+        };
+        let (envelope, encoded) = signed_message(&env, &client, cancellation.clone().to_xdr(&env));
+        let signature: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.cancel_game(&cancellation, &envelope, &signature);
+
+        assert!(client.is_game_cancelled(&game_id));
+        assert!(!client.get_public_setting(&game_id).active);
+        assert_eq!(client.get_stake(&summiter), 1000);
+        assert_eq!(client.get_stake(&summiter2), 1000);
+
+        // the bettors get everything back before the game would have started
+        assert_eq!(client.claim_refund(&user, &game_id), 1000);
+        assert_eq!(token_usd_client.balance(&user), usd_balance);
+        assert_eq!(token_trust_client.balance(&user), trust_balance);
+        assert_eq!(client.get_honesty_points(&user), 0);
+        assert!(client.try_bet(&user, &bet).is_err());
+        assert!(client.try_cancel_game(&cancellation, &envelope, &signature).is_err());
+    }
+    #[test]
+    fn test_unbond_and_withdraw_stake() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 100_000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+        assert_eq!(client.get_stake(&summiter), 1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        draw_game(&client, game_id);
+        // the stake of the selected summiters is now assigned to the game
+        assert_eq!(client.get_stake(&summiter), 0);
+        assert_eq!(client.get_summiter_bond(&summiter).games, vec![&env, game_id]);
+
+        // the cooldown covers the assessment of the assigned game
+        let timeline = client.get_timeline(&game_id);
+        let available_at = client.request_unbond(&summiter);
+        assert_eq!(available_at, 100_000 + timeline.assessment_window);
+        assert!(client.get_summiter_bond(&summiter).unbonding);
+        assert!(client.try_request_result_summiter(&summiter, &1000).is_err());
+        assert!(client.try_withdraw_stake(&summiter).is_err());
+
+        let cancellation = GameCancellation {
+            game_id,
+            reason: String::from_str(&env, "Postponed by the league"),
+        };
+        let (envelope, encoded) = signed_message(&env, &client, cancellation.clone().to_xdr(&env));
+        let signature: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.cancel_game(&cancellation, &envelope, &signature);
+        // the stake comes back but the unbonding summiter is not listed again
+        assert_eq!(client.get_stake(&summiter), 1000);
+        let leaderboard = client.get_leaderboard(&0, &10);
+        assert_eq!(leaderboard.len(), 1);
+        assert_eq!(leaderboard.get(0).unwrap().0, summiter2);
+
+        set_ledger_timestamp(&env, available_at - 1);
+        assert!(client.try_withdraw_stake(&summiter).is_err());
+        set_ledger_timestamp(&env, available_at);
+        assert_eq!(client.withdraw_stake(&summiter), 1000);
+        assert_eq!(token_usd_client.balance(&summiter), 100_000_000);
+        assert_eq!(client.get_stake(&summiter), 0);
+        assert!(!client.get_summiter_bond(&summiter).unbonding);
+        assert!(client.try_withdraw_stake(&summiter).is_err());
+    }
+    #[test]
+    fn test_unbond_waits_for_locked_games() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+
+        // the main summiter leaves while its stake is locked for the game
+        let available_at = client.request_unbond(&summiter2);
+        set_ledger_timestamp(&env, 2100);
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+
+        // the cooldown is over but the game still holds the stake
+        set_ledger_timestamp(&env, available_at);
+        assert_eq!(
+            client.try_withdraw_stake(&summiter2),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::GamesStillAssigned as u32
+            )))
+        );
+
+        // the settlement gives the stake back without listing the summiter again
+        client.execute_distribution(&game_id, &game_id);
+        assert!(client.get_summiter_bond(&summiter2).games.is_empty());
+        assert_eq!(client.withdraw_stake(&summiter2), 1000);
+        let leaderboard = client.get_leaderboard(&0, &10);
+        assert!(leaderboard.iter().all(|(listed, _)| listed != summiter2));
+        assert!(leaderboard.iter().any(|(listed, _)| listed == summiter));
+        assert!(!client.get_summiter_bond(&summiter2).listed);
+    }
+    #[test]
+    fn test_unbond_after_paused_game_never_ruled() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+
+        // the main summiter leaves and its result is disputed
+        let available_at = client.request_unbond(&summiter2);
+        set_ledger_timestamp(&env, 2100);
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &game_id, &game_id, &AssessmentKey::reject);
+        assert!(client.get_result(&game_id).pause);
+
+        // the cooldown and the assessment are over but the supreme court still has time to rule
+        let assessment_end = 2000 + 5 * 3600;
+        assert!(available_at > assessment_end);
+        set_ledger_timestamp(&env, available_at);
+        assert_eq!(
+            client.try_withdraw_stake(&summiter2),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::GamesStillAssigned as u32
+            )))
+        );
+
+        // it never rules, past its window the lock is released and the stake withdrawn
+        set_ledger_timestamp(&env, assessment_end + SUPREME_COURT_WINDOW);
+        assert_eq!(client.withdraw_stake(&summiter2), 1000);
+        assert_eq!(token_usd_client.balance(&summiter2), 100_000_000);
+        assert!(client.get_summiter_bond(&summiter2).games.is_empty());
+        // the checker keeps its stake locked until it leaves too
+        assert!(client.get_summiter_bond(&summiter).games.contains(&game_id));
+    }
+    #[test]
+    fn test_stake_locked_per_game() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        for game_id in 1..4 {
+            let game = Game {
+                id: game_id,
+                startTime: 1000,
+                endTime: 2000,
+                summiter: Address::generate(&env),
+                Checker: soroban_sdk::Vec::new(&env),
+                active: false,
+                league: 1,
+                description: String::from_str(&env, "Team A vs Team B"),
+                team_local: 33,
+                team_away: 44,
+            };
+            let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+            let signaturex: BytesN<64> =
+                BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+            client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        }
+        client.set_stake_lock(&admin, &600);
+        assert_eq!(client.get_stake_lock(), 600);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: 1,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: 1,
+        };
+        client.bet(&user, &bet);
+        let betx = Bet {
+            id: 2,
+            Setting: 1,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: 1,
+        };
+        client.bet(&user2, &betx);
+        draw_game(&client, 1);
+        // only the lock leaves the free stake and the summiters stay listed
+        assert_eq!(client.get_stake(&summiter), 400);
+        assert_eq!(client.get_stake(&summiter2), 400);
+        assert_eq!(client.get_leaderboard(&0, &10).len(), 2);
+
+        // nobody has enough free stake for a second game, the admin summits it
+        client.bet(&user, &Bet { id: 3, Setting: 2, gameid: 2, ..bet.clone() });
+        client.bet(&user2, &Bet { id: 4, Setting: 2, gameid: 2, ..betx.clone() });
+        draw_game(&client, 2);
+        assert_eq!(client.get_game(&2).summiter, admin);
+        assert_eq!(client.get_game(&2).Checker.len(), 0);
+
+        // a top up lets the summiter back a third game at the same time
+        client.request_result_summiter(&summiter, &1000);
+        assert_eq!(client.get_stake(&summiter), 1400);
+        client.bet(&user, &Bet { id: 5, Setting: 3, gameid: 3, ..bet.clone() });
+        client.bet(&user2, &Bet { id: 6, Setting: 3, gameid: 3, ..betx.clone() });
+        draw_game(&client, 3);
+        assert_eq!(client.get_game(&3).summiter, summiter);
+        assert_eq!(client.get_stake(&summiter), 800);
+        assert_eq!(client.get_summiter_bond(&summiter).games, vec![&env, 1, 3]);
+        assert!(client.try_set_stake_lock(&user, &600).is_err());
+    }
+    #[test]
+    fn test_commit_reveal_selection() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        // the admin commits to a secret before the game is activated and bonds it
+        let secret = BytesN::from_array(&env, &[7; 32]);
+        let commitment: BytesN<32> = env.crypto().sha256(&Bytes::from(secret.clone())).to_bytes();
+        adm_usd.mint(&admin, &100_000_000);
+        client.commit_seed(&admin, &game_id, &commitment);
+        assert_eq!(token_usd_client.balance(&admin), 100_000_000 - SEED_COMMIT_BOND);
+        assert_eq!(client.get_selection_draw(&game_id).bond, SEED_COMMIT_BOND);
+        assert!(client.try_commit_seed(&admin, &game_id, &commitment).is_err());
+        assert!(client.try_commit_seed(&user, &2, &commitment).is_err());
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        // the activating bet only records the entropy, the draw waits for the secret
+        assert!(client.get_game(&game_id).active);
+        assert_eq!(client.get_game(&game_id).summiter, admin);
+        let draw = client.get_selection_draw(&game_id);
+        assert_eq!(draw.commitment, commitment);
+        assert!(!draw.drawn);
+        // the entropy stays hidden until the draw
+        assert_eq!(draw.entropy, BytesN::from_array(&env, &[0; 32]));
+        assert_eq!(client.get_stake(&summiter), 1000);
+        // nobody else can draw before the reveal deadline
+        env.ledger().set_sequence_number(env.ledger().sequence() + 1);
+        assert!(client.try_fallback_seed(&game_id).is_err());
+
+        assert!(client
+            .try_reveal_seed(&game_id, &BytesN::from_array(&env, &[8; 32]))
+            .is_err());
+        let seed = client.reveal_seed(&game_id, &secret);
+        let draw = client.get_selection_draw(&game_id);
+        let mut data = Bytes::from(secret.clone());
+        data.append(&Bytes::from(draw.entropy.clone()));
+        assert_eq!(seed, env.crypto().sha256(&data).to_bytes());
+        assert_eq!(token_usd_client.balance(&admin), 100_000_000);
+
+        // the seed is kept so anyone can repeat the draw
+        assert!(draw.drawn);
+        assert_eq!(draw.seed, seed);
+        let game = client.get_game(&game_id);
+        assert!(game.summiter == summiter || game.summiter == summiter2);
+        assert_eq!(game.Checker.len(), 1);
+        assert_eq!(client.get_stake(&summiter) + client.get_stake(&summiter2), 0);
+        assert!(client.try_reveal_seed(&game_id, &secret).is_err());
+    }
+    #[test]
+    fn test_seed_fallback_after_deadline() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            _,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let secret = BytesN::from_array(&env, &[7; 32]);
+        let commitment: BytesN<32> = env.crypto().sha256(&Bytes::from(secret.clone())).to_bytes();
+        adm_usd.mint(&admin, &100_000_000);
+        client.commit_seed(&admin, &game_id, &commitment);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        client.bet(&user2, &Bet { id: 2, bet: WINNER_AWAY, ..bet.clone() });
+        // the window is cut at kickoff
+        let draw = client.get_selection_draw(&game_id);
+        assert_eq!(draw.reveal_deadline, 1000);
+        assert_eq!(client.get_game(&game_id).summiter, admin);
+        assert!(client.try_fallback_seed(&game_id).is_err());
+
+        // the admin withholds the secret, once the deadline passes it can't be revealed
+        set_ledger_timestamp(&env, 1001);
+        env.ledger().set_sequence_number(env.ledger().sequence() + 1);
+        assert!(client.try_reveal_seed(&game_id, &secret).is_err());
+        let seed = client.fallback_seed(&game_id);
+        // the bond is forfeited to the fines of the game
+        assert_eq!(token_usd_client.balance(&admin), 100_000_000 - SEED_COMMIT_BOND);
+        let fine = env.as_contract(&client.address, || storage::get_Fine(env.clone(), game_id));
+        assert_eq!(fine, SEED_COMMIT_BOND);
+
+        let draw = client.get_selection_draw(&game_id);
+        assert!(draw.drawn);
+        assert_eq!(draw.seed, seed);
+        let game = client.get_game(&game_id);
+        assert!(game.summiter == summiter || game.summiter == summiter2);
+        assert_eq!(game.Checker.len(), 1);
+        assert!(client.try_fallback_seed(&game_id).is_err());
+    }
+    // draws game 1 without a commitment, the host entropy is replaced just before the fallback
+    fn fallback_draw(host_seed: [u8; 32]) -> ([u8; 32], Vec<std::string::String>, bool) {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            _,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        for _ in 0..4 {
+            let summiter = Address::generate(&env);
+            adm_usd.mint(&summiter, &100_000_000);
+            client.request_result_summiter(&summiter, &1000);
+        }
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        client.bet(&user2, &Bet { id: 2, bet: WINNER_AWAY, ..bet.clone() });
+        // a summiter joining once the activation is known isn't a candidate
+        let late = Address::generate(&env);
+        adm_usd.mint(&late, &100_000_000);
+        client.request_result_summiter(&late, &1000);
+        env.ledger().set_sequence_number(env.ledger().sequence() + 1);
+
+        env.host().set_base_prng_seed(host_seed).unwrap();
+        let seed = client.fallback_seed(&game_id);
+        let draw = client.get_selection_draw(&game_id);
+        let mut data = Bytes::from(draw.entropy.clone());
+        data.extend_from_array(&draw.activated_ledger.to_be_bytes());
+        data.extend_from_array(&draw.reveal_deadline.to_be_bytes());
+        assert_eq!(seed, env.crypto().sha256(&data).to_bytes());
+
+        let game = client.get_game(&game_id);
+        let mut drawn = Vec::new();
+        for addr in core::iter::once(game.summiter.clone()).chain(game.Checker.iter()) {
+            let mut buf = [0u8; 56];
+            addr.to_string().copy_into_slice(&mut buf);
+            drawn.push(std::string::String::from_utf8(buf.to_vec()).unwrap());
+        }
+        let late_drawn = game.summiter == late || game.Checker.contains(&late);
+        (seed.to_array(), drawn, late_drawn)
+    }
+    #[test]
+    fn test_fallback_seed_is_fixed_before_the_call() {
+        // calling again in the same state makes the same draw whatever the host entropy
+        let (seed, drawn, late_drawn) = fallback_draw([0; 32]);
+        let (seed2, drawn2, late_drawn2) = fallback_draw([9; 32]);
+        assert_eq!(seed, seed2);
+        assert_eq!(drawn, drawn2);
+        assert!(drawn.len() > 1);
+        assert!(!late_drawn && !late_drawn2);
+    }
+    #[test]
+    fn test_draw_waits_for_a_later_ledger() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            _,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        for game_id in [1, 2] {
+            let game = Game {
+                id: game_id,
+                startTime: 1000,
+                endTime: 2000,
+                summiter: Address::generate(&env),
+                Checker: soroban_sdk::Vec::new(&env),
+                active: false,
+                league: 1,
+                description: String::from_str(&env, "Team A vs Team B"),
+                team_local: 33,
+                team_away: 44,
+            };
+            let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+            let signaturex: BytesN<64> =
+                BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+            client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        }
+
+        let summiter = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let bet = Bet {
+            id: 1,
+            Setting: 1,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: 1,
+        };
+        client.bet(&user, &bet);
+        client.bet(&user2, &Bet { id: 2, bet: WINNER_AWAY, ..bet.clone() });
+        // without a commitment the activation only records the entropy
+        let draw = client.get_selection_draw(&1);
+        assert!(!draw.drawn);
+        assert_eq!(draw.activated_ledger, env.ledger().sequence());
+        assert_eq!(client.get_game(&1).summiter, admin);
+        // the activating transaction can't draw
+        assert!(client.try_fallback_seed(&1).is_err());
+
+        env.ledger().set_sequence_number(env.ledger().sequence() + 1);
+        let seed = client.fallback_seed(&1);
+        assert_eq!(client.get_selection_draw(&1).seed, seed);
+        assert_eq!(client.get_game(&1).summiter, summiter);
+
+        // a secret that isn't overdue gets its bond back when the game is cancelled
+        let secret = BytesN::from_array(&env, &[7; 32]);
+        let commitment: BytesN<32> = env.crypto().sha256(&Bytes::from(secret.clone())).to_bytes();
+        adm_usd.mint(&admin, &100_000_000);
+        client.commit_seed(&admin, &2, &commitment);
+        client.bet(&user, &Bet { id: 3, Setting: 2, gameid: 2, ..bet.clone() });
+        client.bet(&user2, &Bet { id: 4, Setting: 2, gameid: 2, bet: WINNER_AWAY, ..bet.clone() });
+        let cancellation = GameCancellation {
+            game_id: 2,
+            reason: String::from_str(&env, "Postponed by the league"),
+        };
+        let (envelope, encoded) = signed_message(&env, &client, cancellation.clone().to_xdr(&env));
+        let signature: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.cancel_game(&cancellation, &envelope, &signature);
+        assert_eq!(token_usd_client.balance(&admin), 100_000_000);
+        assert_eq!(client.get_selection_draw(&2).bond, 0);
+    }
+    #[test]
+    fn test_selection_policy() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        for game_id in 1..4 {
+            let game = Game {
+                id: game_id,
+                startTime: 1000,
+                endTime: 2000,
+                summiter: Address::generate(&env),
+                Checker: soroban_sdk::Vec::new(&env),
+                active: false,
+                league: 1,
+                description: String::from_str(&env, "Team A vs Team B"),
+                team_local: 33,
+                team_away: 44,
+            };
+            let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+            let signaturex: BytesN<64> =
+                BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+            client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        }
+        let policy = SelectionPolicy {
+            pool_size: 3,
+            checkers: 1,
+            value_per_checker: 1000,
+            max_checkers: 2,
+            exclusion_window: 5000,
+            min_history: 0,
+        };
+        client.set_selection_policy(&admin, &policy);
+        assert_eq!(client.get_selection_policy(), policy);
+        assert!(client
+            .try_set_selection_policy(&admin, &SelectionPolicy { pool_size: 0, ..policy.clone() })
+            .is_err());
+        assert!(client
+            .try_set_selection_policy(&admin, &SelectionPolicy { checkers: 3, ..policy.clone() })
+            .is_err());
+        assert!(client.try_set_selection_policy(&user, &policy).is_err());
+
+        let mut summiters = SorobanVec::new(&env);
+        for _ in 0..4 {
+            let summiter = Address::generate(&env);
+            adm_usd.mint(&summiter, &100_000_000);
+            client.request_result_summiter(&summiter, &3000);
+            summiters.push_back(summiter);
+        }
+
+        set_ledger_timestamp(&env, 100);
+        let bet = Bet {
+            id: 1,
+            Setting: 1,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: 1,
+        };
+        let betx = Bet {
+            id: 2,
+            Setting: 1,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: 1,
+        };
+        client.bet(&user, &bet);
+        client.bet(&user2, &betx);
+        draw_game(&client, 1);
+        // the value bet adds checkers up to the maximum, drawn from the 3 best candidates
+        let game = client.get_game(&1);
+        assert_eq!(game.Checker.len(), 2);
+        assert!(game.summiter != summiters.get(0).unwrap());
+        assert!(!game.Checker.contains(&summiters.get(0).unwrap()));
+
+        // the summiters just selected are left out of the next draw
+        client.bet(&user, &Bet { id: 3, Setting: 2, gameid: 2, ..bet.clone() });
+        client.bet(&user2, &Bet { id: 4, Setting: 2, gameid: 2, ..betx.clone() });
+        draw_game(&client, 2);
+        let game = client.get_game(&2);
+        assert_eq!(game.summiter, summiters.get(0).unwrap());
+        assert_eq!(game.Checker.len(), 0);
+
+        // nobody reaches the minimum history, the admin summits
+        client.set_selection_policy(
+            &admin,
+            &SelectionPolicy {
+                min_history: 1,
+                exclusion_window: 0,
+                ..policy.clone()
+            },
+        );
+        set_ledger_timestamp(&env, 500);
+        client.bet(&user, &Bet { id: 5, Setting: 3, gameid: 3, ..bet.clone() });
+        client.bet(&user2, &Bet { id: 6, Setting: 3, gameid: 3, ..betx.clone() });
+        draw_game(&client, 3);
+        assert_eq!(client.get_game(&3).summiter, admin);
+    }
+    #[test]
+    fn test_checker_count_fixed_at_draw() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game = Game {
+            id: 1,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        let policy = SelectionPolicy {
+            pool_size: 5,
+            checkers: 1,
+            value_per_checker: 2000,
+            max_checkers: 3,
+            exclusion_window: 0,
+            min_history: 0,
+        };
+        client.set_selection_policy(&admin, &policy);
+        for _ in 0..5 {
+            let summiter = Address::generate(&env);
+            adm_usd.mint(&summiter, &100_000_000);
+            client.request_result_summiter(&summiter, &3000);
+        }
+
+        set_ledger_timestamp(&env, 100);
+        let bet = Bet {
+            id: 1,
+            Setting: 1,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: 1,
+        };
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        client.bet(&user, &bet);
+        client.bet(&user2, &Bet { id: 2, bet: WINNER_AWAY, ..bet.clone() });
+        draw_game(&client, 1);
+        let drawn = client.get_game(&1);
+        assert_eq!(drawn.Checker.len(), 2);
+
+        // the value bet after the draw doesn't add checkers, so no bet can pick them with a seed it knows
+        let user3 = Address::generate(&env);
+        adm_usd.mint(&user3, &100_000_000);
+        adm_trust.mint(&user3, &100_000_000);
+        client.bet(&user3, &Bet { id: 3, amount_bet: 4000, ..bet.clone() });
+        let later = client.get_game(&1);
+        assert_eq!(later.summiter, drawn.summiter);
+        assert_eq!(later.Checker, drawn.Checker);
+    }
+    #[test]
+    fn test_paged_leaderboard() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, adm_usd, adm_trust) =
+            create_test_env();
+
+        let mut summiters = SorobanVec::new(&env);
+        for i in 0..40 {
+            let summiter = Address::generate(&env);
+            adm_usd.mint(&summiter, &100_000_000);
+            client.request_result_summiter(&summiter, &(1000 + i * 10));
+            summiters.push_back(summiter);
+        }
+        // the entries are split over pages that stay in score order
+        let index = client.get_leaderboard_index();
+        assert_eq!(index.total, 40);
+        assert!(index.pages.len() > 1);
+        let leaderboard = client.get_leaderboard(&0, &100);
+        assert_eq!(leaderboard.len(), 40);
+        for i in 1..leaderboard.len() {
+            assert!(leaderboard.get(i - 1).unwrap().1 >= leaderboard.get(i).unwrap().1);
+        }
+        assert_eq!(leaderboard.get(0).unwrap().0, summiters.get(39).unwrap());
+        assert_eq!(client.get_leaderboard(&15, &5), leaderboard.slice(15..20));
+        assert_eq!(client.get_leaderboard(&38, &5).len(), 2);
+
+        // a top up moves the summiter to the page of the new score
+        client.request_result_summiter(&summiters.get(0).unwrap(), &1000);
+        let leaderboard = client.get_leaderboard(&0, &100);
+        assert_eq!(leaderboard.len(), 40);
+        assert_eq!(leaderboard.get(0).unwrap().0, summiters.get(0).unwrap());
+        client.request_unbond(&summiters.get(20).unwrap());
+        assert_eq!(client.get_leaderboard_index().total, 39);
+        assert!(!client
+            .get_leaderboard(&0, &100)
+            .iter()
+            .any(|(a, _)| a == summiters.get(20).unwrap()));
+
+        // the leaderboard of the previous versions is moved in batches
+        let old1 = Address::generate(&env);
+        let old2 = Address::generate(&env);
+        let old3 = Address::generate(&env);
+        env.as_contract(&client.address, || {
+            storage::set_legacy_leaderboard(
+                env.clone(),
+                vec![&env, (old1.clone(), 5000), (old2.clone(), 900), (old3.clone(), 10)],
+            );
+        });
+        assert!(client.try_migrate_leaderboard(&user, &2).is_err());
+        assert_eq!(client.migrate_leaderboard(&admin, &2), 1);
+        assert_eq!(client.migrate_leaderboard(&admin, &2), 0);
+        let leaderboard = client.get_leaderboard(&0, &100);
+        assert_eq!(leaderboard.len(), 42);
+        assert_eq!(leaderboard.get(0).unwrap(), (old1, 5000));
+        assert_eq!(leaderboard.get(41).unwrap(), (old3, 10));
+    }
+    #[test]
+    fn test_reschedule_game() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        draw_game(&client, game_id);
+
+        // the game is postponed and gets new summiters
+        set_ledger_timestamp(&env, 100);
+        let schedule = GameSchedule {
+            game_id,
+            version: 1,
+            startTime: 100_000,
+            endTime: 101_000,
+            reselect: true,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, schedule.clone().to_xdr(&env));
+        let signature: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.reschedule_game(&schedule, &envelope, &signature);
+        assert!(client.try_reschedule_game(&schedule, &envelope, &signature).is_err());
+
+        let rescheduled = client.get_game(&game_id);
+        assert_eq!(rescheduled.startTime, 100_000);
+        assert_eq!(rescheduled.endTime, 101_000);
+        assert!(rescheduled.active);
+        // the old summiters are released and the new ones are drawn by a later call
+        assert_eq!(rescheduled.summiter, admin);
+        assert_eq!(client.get_stake(&summiter) + client.get_stake(&summiter2), 2000);
+        draw_game(&client, game_id);
+        let rescheduled = client.get_game(&game_id);
+        assert!(rescheduled.summiter == summiter || rescheduled.summiter == summiter2);
+        assert_eq!(client.get_stake(&summiter) + client.get_stake(&summiter2), 0);
+
+        // after the old kickoff the bettors can still opt out, without fee
+        set_ledger_timestamp(&env, 1500);
+        let usd_balance = token_usd_client.balance(&user2);
+        assert_eq!(client.cancel_bet(&user2, &game_id, &WINNER_AWAY), 1000);
+        assert_eq!(token_usd_client.balance(&user2), usd_balance + 1000);
+
+        // a position placed after the reschedule pays the fee
+        let user3 = Address::generate(&env);
+        adm_usd.mint(&user3, &100_000_000);
+        adm_trust.mint(&user3, &100_000_000);
+        client.bet(&user3, &betx);
+        assert_eq!(client.cancel_bet(&user3, &game_id, &WINNER_AWAY), 980);
+
+        // the stake placed before can't be topped up or merged while it can opt out
+        assert_eq!(
+            client.try_bet(&user, &bet),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::PositionInOptOutWindow as u32
+            )))
+        );
+        client.bet(&user, &Bet { id: 3, bet: WINNER_DRAW, ..bet.clone() });
+        assert_eq!(
+            client.try_update_bet(&user, &game_id, &WINNER_DRAW, &WINNER_LOCAL),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::PositionInOptOutWindow as u32
+            )))
+        );
+
+        // and so do the ones placed before once the window is over
+        set_ledger_timestamp(&env, 100 + 86_400 + 1);
+        client.update_bet(&user, &game_id, &WINNER_DRAW, &WINNER_LOCAL);
+        assert_eq!(client.cancel_bet(&user, &game_id, &WINNER_LOCAL), 1960);
+    }
+    #[test]
+    fn test_league_timeline_refund() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+
+        // the league has shorter windows than the default ones
+        let timeline = Timeline {
+            summit_window: 100,
+            checker_window: 200,
+            admin_window: 300,
+            assessment_window: 500,
+        };
+        client.set_timeline(&admin, &TimelineScope::League(1), &timeline);
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        assert_eq!(client.get_timeline(&game_id), timeline);
+        assert_eq!(client.get_timeline(&2).admin_window, 3 * 3600);
+        let wrong = Timeline {
+            summit_window: 100,
+            checker_window: 100,
+            admin_window: 300,
+            assessment_window: 500,
+        };
+        assert!(client.try_set_timeline(&admin, &TimelineScope::Default, &wrong).is_err());
+        assert!(client.try_set_timeline(&user, &TimelineScope::Default, &timeline).is_err());
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        draw_game(&client, game_id);
+
+        // no result 300 seconds after the end, the summiters are fined and the bettors refunded
+        set_ledger_timestamp(&env, 2400);
+        assert_eq!(client.claim_refund(&user, &game_id), 1000 + 1000);
+
+        // a game timeline overrides the league one
+        let game2 = Game {
+            id: 2,
+            ..game.clone()
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game2.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        set_ledger_timestamp(&env, 500);
+        client.set_game(&game2, &ADMIN_SIGNER, &envelope, &signaturex);
+        // the game keeps the league timeline it was set with
+        let leagueTimeline = Timeline {
+            summit_window: 200,
+            checker_window: 400,
+            admin_window: 600,
+            assessment_window: 1000,
+        };
+        client.set_timeline(&admin, &TimelineScope::League(1), &leagueTimeline);
+        assert_eq!(client.get_timeline(&2), timeline);
+        let gameTimeline = Timeline {
+            summit_window: 1000,
+            checker_window: 2000,
+            admin_window: 3000,
+            assessment_window: 5000,
+        };
+        client.set_timeline(&admin, &TimelineScope::Game(2), &gameTimeline);
+        assert_eq!(client.get_timeline(&2), gameTimeline);
+    }
+    #[test]
+    fn test_params_timelock_and_snapshot() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game = Game {
+            id: 1,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let mut params = client.get_params();
+        assert_eq!(params.protocol_fee, 10);
+        params.protocol_fee = 90;
+        assert!(client.try_propose_params(&admin, &params).is_err());
+        params.protocol_fee = 15;
+        params.trust_percentage = 10;
+        assert!(client.try_propose_params(&user, &params).is_err());
+        let effective_at = client.propose_params(&admin, &params);
+        // the new values wait 24 hours
+        assert_eq!(effective_at, env.ledger().timestamp() as u32 + PARAMS_TIMELOCK);
+        assert_eq!(client.get_params_proposal().effective_at, effective_at);
+        assert!(client.try_apply_params().is_err());
+
+        set_ledger_timestamp(&env, effective_at);
+        client.apply_params();
+        assert_eq!(client.get_params(), params);
+        assert_eq!(client.get_params_proposal().effective_at, 0);
+        // the game set before keeps the parameters it was created with
+        assert_eq!(client.get_game_params(&1).protocol_fee, 10);
+
+        let game2 = Game {
+            id: 2,
+            startTime: effective_at + 1000,
+            endTime: effective_at + 2000,
+            ..game.clone()
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game2.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game2, &ADMIN_SIGNER, &envelope, &signaturex);
+        assert_eq!(client.get_game_params(&2), params);
+
+        // the new trust percentage applies to the bets of the new game
+        let trust_balance = token_trust_client.balance(&user);
+        let bet = Bet {
+            id: 1,
+            Setting: 2,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: 2,
+        };
+        client.bet(&user, &bet);
+        assert_eq!(token_trust_client.balance(&user), trust_balance - 100);
+    }
+    #[test]
+    fn test_exact_score_and_custom_settlement() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        // outcome 0 is 1-0, 1 is 2-1 and 2 is any other score
+        let exactSetting = PublicBet {
+            id: 12,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Correct score"),
+            market: MarketType::ExactScore(vec![
+                &env,
+                Score { local: 1, away: 0 },
+                Score { local: 2, away: 1 },
+            ]),
+        };
+        let exactId = client.set_public_bet(&admin, &exactSetting, &game_id);
+        let customSetting = PublicBet {
+            id: 13,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "First team to score"),
+            market: MarketType::Custom(vec![
+                &env,
+                String::from_str(&env, "Team A"),
+                String::from_str(&env, "Team B"),
+                String::from_str(&env, "No goals"),
+            ]),
+        };
+        let customId = client.set_public_bet(&admin, &customSetting, &game_id);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        // users vote once per game so the custom setting has its own bettors
+        let user3 = Address::generate(&env);
+        let user4 = Address::generate(&env);
+        for bettor in [user3.clone(), user4.clone()] {
+            adm_usd.mint(&bettor, &100_000_000);
+            adm_trust.mint(&bettor, &100_000_000);
+        }
+        client.bet(
+            &user,
+            &Bet {
+                id: 1,
+                Setting: exactId,
+                bet: 1,
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            },
+        );
+        client.bet(
+            &user2,
+            &Bet {
+                id: 2,
+                Setting: exactId,
+                bet: 2,
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            },
+        );
+        client.bet(
+            &user3,
+            &Bet {
+                id: 3,
+                Setting: customId,
+                bet: 0,
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            },
+        );
+        client.bet(
+            &user4,
+            &Bet {
+                id: 4,
+                Setting: customId,
+                bet: 1,
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            },
+        );
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+        set_ledger_timestamp(&env, 2100);
+
+        // Team B scored first but Team A won 2-1
+        let mut outcomes = Map::new(&env);
+        outcomes.set(customId, 1);
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes,
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &exactId, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &exactId, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user3, &customId, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user4, &customId, &game_id, &AssessmentKey::approve);
+        client.execute_distribution(&game_id, &exactId);
+        client.execute_distribution(&game_id, &customId);
+
+        let exact = client.preview_claim(&user, &exactId);
+        assert_eq!(exact.kind, UserKind::WinnerHonest);
+        assert_eq!(client.preview_claim(&user2, &exactId).kind, UserKind::LoserHonest);
+        let custom = client.preview_claim(&user4, &customId);
+        assert_eq!(custom.kind, UserKind::WinnerHonest);
+        assert_eq!(client.preview_claim(&user3, &customId).kind, UserKind::LoserHonest);
+        assert!(custom.amountUsd > 1000);
+    }
+    #[test]
+    fn test_parlay_claim() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let user2 = Address::generate(&env);
+        let parlayUser = Address::generate(&env);
+        let parlayUser2 = Address::generate(&env);
+        for account in [user2.clone(), parlayUser.clone(), parlayUser2.clone()] {
+            adm_usd.mint(&account, &100_000_000);
+            adm_trust.mint(&account, &100_000_000);
+        }
+        adm_usd.mint(&admin, &100_000_000);
+        client.fund_parlay_pool(&admin, &10_000);
+
+        // two games where the local team pays 1.7x in the public setting, the losing stake net of the 30% cut
+        for game_id in [1, 2] {
+            let game = Game {
+                id: game_id,
+                startTime: 1000,
+                endTime: 2000,
+                summiter: Address::generate(&env),
+                Checker: soroban_sdk::Vec::new(&env),
+                active: false,
+                league: 1,
+                description: String::from_str(&env, "Team A vs Team B"),
+                team_local: 33,
+                team_away: 44,
+            };
+            let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+            let signaturex: BytesN<64> =
+                BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+            client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        }
+        let parlay = Parlay {
+            id: 1,
+            legs: vec![
+                &env,
+                ParlayLeg {
+                    setting: 1,
+                    outcome: WINNER_LOCAL,
+                },
+                ParlayLeg {
+                    setting: 2,
+                    outcome: WINNER_LOCAL,
+                },
+            ],
+            amount_bet: 100,
+            claimed: false,
+        };
+        let parlayId = client.bet_parlay(&parlayUser, &parlay);
+        let mut losingParlay = parlay.clone();
+        losingParlay.legs.set(
+            1,
+            ParlayLeg {
+                setting: 2,
+                outcome: WINNER_AWAY,
+            },
+        );
+        // the same id sent again gets the next one
+        let losingId = client.bet_parlay(&parlayUser2, &losingParlay);
+        assert_eq!(losingId, parlayId + 1);
+        // each ticket reserves its winnings at the highest odds, the pool can't back a third one
+        assert_eq!(client.get_parlay_reserved(), 2 * 4900);
+        assert_eq!(
+            client.try_bet_parlay(&user2, &parlay),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::BalanceError as u32
+            )))
+        );
+        assert_eq!(
+            client.get_id_owner(&IdKind::Parlay, &losingId),
+            Some(parlayUser2.clone())
+        );
+        let parlay_usd_balance = token_usd_client.balance(&parlayUser);
+
+        for game_id in [1, 2] {
+            let summiter = Address::generate(&env);
+            let summiter2 = Address::generate(&env);
+            adm_usd.mint(&summiter, &100_000_000);
+            adm_usd.mint(&summiter2, &100_000_000);
+            client.request_result_summiter(&summiter, &1000);
+            client.request_result_summiter(&summiter2, &1000);
+            let bet = Bet {
+                id: 1,
+                Setting: game_id,
+                bet: WINNER_LOCAL,
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            };
+            client.bet(&user, &bet);
+            let betx = Bet {
+                id: 2,
+                Setting: game_id,
+                bet: WINNER_AWAY,
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            };
+            client.bet(&user2, &betx);
+            set_ledger_timestamp(&env, 2100);
+            let result = ResultGame {
+                id: 1,
+                gameid: game_id,
+                result: BetKey::Team_local,
+                score: Score { local: 2, away: 1 },
+                half_time: HalfTime::NotReported,
+                outcomes: Map::new(&env),
+                pause: false,
+                description: String::from_str(&env, "Final Score 2-1"),
+                distribution_executed: false,
+            };
+            // the summiters of the first game can be drawn for the second one
+            let summiter2 = client.get_game(&game_id).summiter;
+            client.summitResult(&summiter2, &result);
+            client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+            client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+            client.execute_distribution(&game_id, &game_id);
+            set_ledger_timestamp(&env, 0);
+        }
+        set_ledger_timestamp(&env, 2100);
+
+        // 100 * 1.7 * 1.7, the winnings come from the parlay pool
+        assert_eq!(client.claim_parlay(&parlayUser, &parlayId), 289);
+        assert_eq!(token_usd_client.balance(&parlayUser), parlay_usd_balance + 289);
+        assert_eq!(client.get_parlay_pool(), 10_000 - 189);
+        // the losing stake goes to the pool
+        assert_eq!(client.claim_parlay(&parlayUser2, &losingId), 0);
+        assert_eq!(client.get_parlay_pool(), 10_000 - 189 + 100);
+        assert!(client.get_parlay(&parlayUser, &parlayId).claimed);
+        assert_eq!(client.get_parlay_reserved(), 0);
+    }
+    #[test]
+    fn test_parlay_disputed_leg_void() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let user2 = Address::generate(&env);
+        let parlayUser = Address::generate(&env);
+        for account in [user2.clone(), parlayUser.clone()] {
+            adm_usd.mint(&account, &100_000_000);
+            adm_trust.mint(&account, &100_000_000);
+        }
+        adm_usd.mint(&admin, &100_000_000);
+        client.fund_parlay_pool(&admin, &10_000);
+        for game_id in [1, 2] {
+            let game = Game {
+                id: game_id,
+                startTime: 1000,
+                endTime: 2000,
+                summiter: Address::generate(&env),
+                Checker: soroban_sdk::Vec::new(&env),
+                active: false,
+                league: 1,
+                description: String::from_str(&env, "Team A vs Team B"),
+                team_local: 33,
+                team_away: 44,
+            };
+            let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+            let signaturex: BytesN<64> =
+                BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+            client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        }
+        let parlay = Parlay {
+            id: 1,
+            legs: vec![
+                &env,
+                ParlayLeg {
+                    setting: 1,
+                    outcome: WINNER_LOCAL,
+                },
+                ParlayLeg {
+                    setting: 2,
+                    outcome: WINNER_LOCAL,
+                },
+            ],
+            amount_bet: 100,
+            claimed: false,
+        };
+        let parlayId = client.bet_parlay(&parlayUser, &parlay);
+
+        for game_id in [1, 2] {
+            for (bettor, outcome) in [(user.clone(), WINNER_LOCAL), (user2.clone(), WINNER_AWAY)] {
+                let bet = Bet {
+                    id: 1,
+                    Setting: game_id,
+                    bet: outcome,
+                    amount_bet: 1000,
+                    betType: BetType::Public,
+                    gameid: game_id,
+                };
+                client.bet(&bettor, &bet);
+            }
+            set_ledger_timestamp(&env, 2100);
+            let result = ResultGame {
+                id: 1,
+                gameid: game_id,
+                result: BetKey::Team_local,
+                score: Score { local: 2, away: 1 },
+                half_time: HalfTime::NotReported,
+                outcomes: Map::new(&env),
+                pause: false,
+                description: String::from_str(&env, "Final Score 2-1"),
+                distribution_executed: false,
+            };
+            client.summitResult(&client.get_game(&game_id).summiter, &result);
+            // the result of the first game is disputed and the supreme court never rules on it
+            if game_id == 1 {
+                client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::reject);
+            } else {
+                client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+                client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+                client.execute_distribution(&game_id, &game_id);
+            }
+            set_ledger_timestamp(&env, 0);
+        }
+
+        // the disputed leg waits for the court during the assessment window
+        let timeline = client.get_timeline(&1);
+        set_ledger_timestamp(&env, 2000 + timeline.assessment_window);
+        assert!(client.try_claim_parlay(&parlayUser, &parlayId).is_err());
+        // and is void after it, the stake isn't locked forever
+        set_ledger_timestamp(&env, 2000 + timeline.assessment_window + 1);
+        assert_eq!(client.claim_parlay(&parlayUser, &parlayId), 170);
+        assert_eq!(client.get_parlay_reserved(), 0);
+    }
+    #[test]
+    fn test_parlay_dust_bet_doesnt_price_leg() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let user2 = Address::generate(&env);
+        let dustUser = Address::generate(&env);
+        let parlayUser = Address::generate(&env);
+        let parlayUser2 = Address::generate(&env);
+        for account in [
+            user2.clone(),
+            dustUser.clone(),
+            parlayUser.clone(),
+            parlayUser2.clone(),
+        ] {
+            adm_usd.mint(&account, &100_000_000);
+            adm_trust.mint(&account, &100_000_000);
+        }
+        adm_usd.mint(&admin, &100_000_000);
+        client.fund_parlay_pool(&admin, &20_000);
+        for game_id in [1, 2, 3] {
+            let game = Game {
+                id: game_id,
+                startTime: 1000,
+                endTime: 2000,
+                summiter: Address::generate(&env),
+                Checker: soroban_sdk::Vec::new(&env),
+                active: false,
+                league: 1,
+                description: String::from_str(&env, "Team A vs Team B"),
+                team_local: 33,
+                team_away: 44,
+            };
+            let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+            let signaturex: BytesN<64> =
+                BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+            client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        }
+        // the same ticket on a local win nobody backed, with and without a dust bet on it
+        let parlay = Parlay {
+            id: 1,
+            legs: vec![
+                &env,
+                ParlayLeg {
+                    setting: 1,
+                    outcome: WINNER_LOCAL,
+                },
+                ParlayLeg {
+                    setting: 3,
+                    outcome: WINNER_LOCAL,
+                },
+            ],
+            amount_bet: 100,
+            claimed: false,
+        };
+        let parlayId = client.bet_parlay(&parlayUser, &parlay);
+        let mut dustParlay = parlay.clone();
+        dustParlay.legs.set(
+            0,
+            ParlayLeg {
+                setting: 2,
+                outcome: WINNER_LOCAL,
+            },
+        );
+        let dustId = client.bet_parlay(&parlayUser2, &dustParlay);
+
+        for game_id in [1, 2, 3] {
+            let bet = Bet {
+                id: 1,
+                Setting: game_id,
+                bet: if game_id == 3 { WINNER_LOCAL } else { WINNER_DRAW },
+                amount_bet: 1000,
+                betType: BetType::Public,
+                gameid: game_id,
+            };
+            client.bet(&user, &bet);
+            client.bet(&user2, &Bet { id: 2, bet: WINNER_AWAY, ..bet.clone() });
+            if game_id == 2 {
+                client.bet(&dustUser, &Bet { id: 3, bet: WINNER_LOCAL, amount_bet: 1, ..bet.clone() });
+            }
+            set_ledger_timestamp(&env, 2100);
+            let result = ResultGame {
+                id: 1,
+                gameid: game_id,
+                result: BetKey::Team_local,
+                score: Score { local: 2, away: 1 },
+                half_time: HalfTime::NotReported,
+                outcomes: Map::new(&env),
+                pause: false,
+                description: String::from_str(&env, "Final Score 2-1"),
+                distribution_executed: false,
+            };
+            let summiter = client.get_game(&game_id).summiter;
+            client.summitResult(&summiter, &result);
+            client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+            client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+            if game_id == 2 {
+                client.assessResult(&dustUser, &game_id, &game_id, &AssessmentKey::approve);
+            }
+            client.execute_distribution(&game_id, &game_id);
+            set_ledger_timestamp(&env, 0);
+        }
+        set_ledger_timestamp(&env, 2100);
+
+        // the dust bet is too thin a share to price its leg, both pay 100 * 1 * 1.7
+        assert_eq!(client.claim_parlay(&parlayUser, &parlayId), 170);
+        assert_eq!(client.claim_parlay(&parlayUser2, &dustId), 170);
+    }
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_summit_result_score_mismatch() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, adm_usd, adm_trust) =
+            create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        set_ledger_timestamp(&env, 2100);
+
+        // the away team can't win a game that finished 2-1
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_away,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::Score(Score { local: 1, away: 0 }),
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result); // Should panic
+    }
+    #[test]
+    #[should_panic(expected = "Error(Contract, #222)")]
+    fn test_set_public_bet_not_admin() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, _, _) =
+            create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let publicSetting = PublicBet {
+            id: 12,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Public Bet 2"),
+            market: MarketType::Winner,
+        };
+        client.set_public_bet(&user, &publicSetting, &game_id); // Should panic
+    }
+    #[test]
+    fn test_read_views() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, adm_usd, adm_trust) =
+            create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        assert_eq!(client.get_game(&game_id), game);
+        assert_eq!(client.get_game_settings(&game_id), vec![&env, game_id]);
+        assert_eq!(client.get_public_setting(&game_id).gameid, game_id);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+        assert_eq!(client.get_leaderboard(&0, &10).len(), 2);
+        assert_eq!(client.get_leaderboard(&1, &10).len(), 1);
+        assert_eq!(client.get_leaderboard(&2, &10).len(), 0);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        assert_eq!(client.get_bet(&user, &game_id), vec![&env, bet.clone()]);
+        assert_eq!(client.get_honesty_points(&user), -20);
+        assert!(client.get_game(&game_id).active);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+        assert_eq!(client.get_game(&game_id).summiter, summiter2);
+
+        set_ledger_timestamp(&env, 2100);
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        assert_eq!(client.get_result(&game_id), result);
+        // the first result gets the first result id and the summiter owns it
+        assert_eq!(
+            client.get_id_owner(&IdKind::Result, &client.get_result(&game_id).id),
+            Some(summiter2.clone())
+        );
+        client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+        assert_eq!(client.get_assessment(&game_id).UsersApprove.len(), 2);
+
+        client.execute_distribution(&game_id, &game_id);
+        let pools = client.get_pools(&game_id);
+        assert!(pools.distributed);
+        assert_eq!(pools.winnerPool, 1000);
+        assert_eq!(pools.loserPool, 1000);
+        assert_eq!(pools.pool, 1400);
+        // the honest summiter claims the fee and gets the locked stake back
+        assert_eq!(client.get_claimable(&summiter2), 400);
+        assert_eq!(client.get_stake(&summiter2), 1000);
+        assert_eq!(client.get_stake(&summiter), 0);
+    }
+    #[test]
+    fn test_preview_claim() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, adm_usd, adm_trust) =
+            create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+
+        let summiter = Address::generate(&env);
+        let summiter2 = Address::generate(&env);
+        adm_usd.mint(&summiter, &100_000_000);
+        adm_usd.mint(&summiter2, &100_000_000);
+        client.request_result_summiter(&summiter, &1000);
+        client.request_result_summiter(&summiter2, &1000);
+
+        let bet = Bet {
+            id: 1,
+            Setting: game_id,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user, &bet);
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let betx = Bet {
+            id: 2,
+            Setting: game_id,
+            bet: WINNER_AWAY,
+            amount_bet: 1000,
+            betType: BetType::Public,
+            gameid: game_id,
+        };
+        client.bet(&user2, &betx);
+        let (summiter, summiter2) = drawn_summiters(&client, game_id, summiter, summiter2);
+        set_ledger_timestamp(&env, 2100);
+
+        let result = ResultGame {
+            id: 1,
+            gameid: game_id,
+            result: BetKey::Team_local,
+            score: Score { local: 2, away: 1 },
+            half_time: HalfTime::NotReported,
+            outcomes: Map::new(&env),
+            pause: false,
+            description: String::from_str(&env, "Final Score 2-1"),
+            distribution_executed: false,
+        };
+        client.summitResult(&summiter2, &result);
+        client.assessResult(&user, &game_id, &game_id, &AssessmentKey::approve);
+        client.assessResult(&user2, &game_id, &game_id, &AssessmentKey::approve);
+        client.execute_distribution(&game_id, &game_id);
+
+        let preview = client.preview_claim(&user, &game_id);
+        assert_eq!(preview.kind, UserKind::WinnerHonest);
+        assert_eq!(preview.amountUsd, 2400);
+        assert_eq!(preview.amountTrust, 300);
+        assert_eq!(preview.points, 50);
+        assert!(!preview.claimed);
+
+        let preview2 = client.preview_claim(&user2, &game_id);
+        assert_eq!(preview2.kind, UserKind::LoserHonest);
+        assert_eq!(preview2.amountUsd, 0);
+        assert_eq!(preview2.amountTrust, 300);
+
+        let nobody = client.preview_claim(&Address::generate(&env), &game_id);
+        assert_eq!(nobody.kind, UserKind::NoBet);
+
+        // the claim pays exactly what the preview said
+        assert_eq!(
+            client.claim(&user, &ClaimType::User, &game_id),
+            (preview.amountUsd, preview.amountTrust)
+        );
+        assert!(client.preview_claim(&user, &game_id).claimed);
+    }
+    #[test]
+    fn test_signed_envelope_replay() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let game = Game {
+            id: 1,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let mut game2 = game.clone();
+        game2.id = 2;
+        // signs an envelope edited after signed_message built it
+        let sign = |envelope: &SignedEnvelope, game: &Game| -> BytesN<64> {
+            let mut message = envelope.clone().to_xdr(&env);
+            message.append(&game.clone().to_xdr(&env));
+            let message: Vec<u8> = message.iter().collect();
+            BytesN::from_array(&env, &key.sign(message.as_slice()).to_bytes())
+        };
+
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        // the nonce of a used envelope can not sign another game
+        assert_eq!(
+            client.try_set_game(&game2, &ADMIN_SIGNER, &envelope, &sign(&envelope, &game2)),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::SignatureReused as u32
+            )))
+        );
+
+        // an envelope past its expiry ledger is refused
+        let (mut expired, _) = signed_message(&env, &client, game2.clone().to_xdr(&env));
+        expired.expiry_ledger = env.ledger().sequence();
+        env.ledger().set_sequence_number(env.ledger().sequence() + 1);
+        assert_eq!(
+            client.try_set_game(&game2, &ADMIN_SIGNER, &expired, &sign(&expired, &game2)),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::SignatureExpired as u32
+            )))
+        );
+
+        // an envelope for another contract or schema version is refused
+        let (mut other, _) = signed_message(&env, &client, game2.clone().to_xdr(&env));
+        other.contract = Address::generate(&env);
+        assert_eq!(
+            client.try_set_game(&game2, &ADMIN_SIGNER, &other, &sign(&other, &game2)),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::InvalidInputError as u32
+            )))
+        );
+        let (mut versioned, _) = signed_message(&env, &client, game2.clone().to_xdr(&env));
+        versioned.version = 2;
+        assert_eq!(
+            client.try_set_game(&game2, &ADMIN_SIGNER, &versioned, &sign(&versioned, &game2)),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::InvalidInputError as u32
+            )))
+        );
+
+        // a fresh envelope is accepted
+        let (envelope2, encoded2) = signed_message(&env, &client, game2.clone().to_xdr(&env));
+        let signaturex2: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded2.as_slice()).to_bytes());
+        assert!(client.set_game(&game2, &ADMIN_SIGNER, &envelope2, &signaturex2));
+    }
+    #[test]
+    fn test_publisher_registry() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let publisher = Keypair::generate(&mut StdRng::seed_from_u64(3));
+        let public_key = BytesN::<32>::from_array(&env, &publisher.public.to_bytes());
+        client.add_publisher(&admin, &7, &public_key, &vec![&env, 1, 3]);
+        assert_eq!(client.get_publisher(&7).pubkey, public_key);
+        assert!(!client.get_publisher(&7).revoked);
+        // the admin signer id is reserved
+        assert!(client
+            .try_add_publisher(&admin, &ADMIN_SIGNER, &public_key, &vec![&env, 1])
+            .is_err());
+        assert!(client
+            .try_add_publisher(&user, &8, &public_key, &vec![&env, 1])
+            .is_err());
+
+        let mut game = Game {
+            id: 1,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let unknown_signer = Err(Ok(soroban_sdk::Error::from_contract_error(
+            BettingError::UnknownSigner as u32,
+        )));
+
+        // the publisher signs the games of its leagues
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &publisher.sign(encoded.as_slice()).to_bytes());
+        assert!(client.set_game(&game, &7, &envelope, &signaturex));
+        let events = env.events().all();
+        let published: SorobanVec<Val> = events
+            .iter()
+            .find(|(_, topics, _)| {
+                let topic: Symbol = topics.get(1).unwrap().try_into_val(&env).unwrap();
+                topic == Symbol::new(&env, "Game_Publisher")
+            })
+            .map(|(_, _, value)| value.try_into_val(&env).unwrap())
+            .unwrap();
+        let key_id: u32 = published.get(1).unwrap().try_into_val(&env).unwrap();
+        assert_eq!(key_id, 7);
+
+        // not the games of another league
+        game.id = 2;
+        game.league = 2;
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &publisher.sign(encoded.as_slice()).to_bytes());
+        assert_eq!(client.try_set_game(&game, &7, &envelope, &signaturex), unknown_signer);
+        // and an unknown key id is refused
+        assert_eq!(client.try_set_game(&game, &9, &envelope, &signaturex), unknown_signer);
+
+        // a revoked publisher can't sign anymore
+        game.league = 3;
+        client.revoke_publisher(&admin, &7);
+        assert!(client.get_publisher(&7).revoked);
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &publisher.sign(encoded.as_slice()).to_bytes());
+        assert_eq!(client.try_set_game(&game, &7, &envelope, &signaturex), unknown_signer);
+        assert!(client.try_revoke_publisher(&admin, &9).is_err());
+
+        // the admin key still signs every league
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        assert!(client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex));
+
+        // the nonces are spent per signer, another publisher can use the one the admin used
+        let publisher2 = Keypair::generate(&mut StdRng::seed_from_u64(5));
+        let public_key2 = BytesN::<32>::from_array(&env, &publisher2.public.to_bytes());
+        client.add_publisher(&admin, &8, &public_key2, &vec![&env, 3]);
+        game.id = 4;
+        let mut message = envelope.clone().to_xdr(&env);
+        message.append(&game.clone().to_xdr(&env));
+        let encoded: Vec<u8> = message.iter().collect();
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &publisher2.sign(encoded.as_slice()).to_bytes());
+        assert!(client.set_game(&game, &8, &envelope, &signaturex));
+        // but not twice
+        game.id = 5;
+        let mut message = envelope.clone().to_xdr(&env);
+        message.append(&game.clone().to_xdr(&env));
+        let encoded: Vec<u8> = message.iter().collect();
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &publisher2.sign(encoded.as_slice()).to_bytes());
+        assert_eq!(
+            client.try_set_game(&game, &8, &envelope, &signaturex),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::SignatureReused as u32
+            )))
+        );
+    }
+    #[test]
+    fn test_fixture_merkle_root() {
+        let (
+            env,
+            client,
+            admin,
+            key,
+            pk,
+            user,
+            token_usd,
+            token_trust,
+            token_usd_client,
+            token_trust_client,
+            adm_usd,
+            adm_trust,
+        ) = create_test_env();
+
+        let publisher = Keypair::generate(&mut StdRng::seed_from_u64(3));
+        let public_key = BytesN::<32>::from_array(&env, &publisher.public.to_bytes());
+        client.add_publisher(&admin, &7, &public_key, &vec![&env, 1]);
+
+        // a matchday of four fixtures, the last one of a league the publisher can't sign
+        let mut games: Vec<Game> = Vec::new();
+        for id in 1..=4 {
+            games.push(Game {
+                id,
+                startTime: 1000,
+                endTime: 2000,
+                summiter: Address::generate(&env),
+                Checker: soroban_sdk::Vec::new(&env),
+                active: false,
+                league: if id == 4 { 2 } else { 1 },
+                description: String::from_str(&env, "Team A vs Team B"),
+                team_local: 33,
+                team_away: 44,
+            });
+        }
+        let leaves: Vec<BytesN<32>> = games
+            .iter()
+            .map(|game| env.crypto().sha256(&game.clone().to_xdr(&env)).to_bytes())
+            .collect();
+        let left = merkle_parent(&env, &leaves[0], &leaves[1]);
+        let right = merkle_parent(&env, &leaves[2], &leaves[3]);
+        let root = merkle_parent(&env, &left, &right);
+        let proof = |leaf: usize| -> SorobanVec<BytesN<32>> {
+            let (sibling, uncle) = match leaf {
+                0 => (&leaves[1], &right),
+                1 => (&leaves[0], &right),
+                2 => (&leaves[3], &left),
+                _ => (&leaves[2], &left),
+            };
+            vec![&env, sibling.clone(), uncle.clone()]
+        };
+
+        // the games can't be set before the root is published
+        let unknown_signer = Err(Ok(soroban_sdk::Error::from_contract_error(
+            BettingError::UnknownSigner as u32,
+        )));
+        assert_eq!(
+            client.try_set_proven_game(&root, &games[0], &proof(0)),
+            unknown_signer
+        );
+
+        // the publisher signs the root once
+        let (envelope, encoded) = signed_message(&env, &client, root.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &publisher.sign(encoded.as_slice()).to_bytes());
+        assert!(client.publish_fixtures(&root, &7, &envelope, &signaturex));
+        let (envelope, encoded) = signed_message(&env, &client, root.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &publisher.sign(encoded.as_slice()).to_bytes());
+        assert!(client
+            .try_publish_fixtures(&root, &7, &envelope, &signaturex)
+            .is_err());
+
+        // anyone sets a game with its proof
+        assert!(client.set_proven_game(&root, &games[0], &proof(0)));
+        assert_eq!(client.get_game(&1), games[0]);
+        assert!(client.try_set_proven_game(&root, &games[0], &proof(0)).is_err());
+
+        // a wrong proof or a game outside the list is refused
+        assert_eq!(
+            client.try_set_proven_game(&root, &games[1], &proof(0)),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::InvalidProof as u32
+            )))
+        );
+        let mut forged = games[1].clone();
+        forged.endTime = 3000;
+        assert_eq!(
+            client.try_set_proven_game(&root, &forged, &proof(1)),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::InvalidProof as u32
+            )))
+        );
+
+        // a game of a league out of the scope of the publisher is refused
+        assert_eq!(
+            client.try_set_proven_game(&root, &games[3], &proof(3)),
+            unknown_signer
+        );
+
+        // several games in one call
+        assert!(client
+            .try_set_games(&root, &vec![&env, games[1].clone()], &SorobanVec::new(&env))
+            .is_err());
+        assert_eq!(
+            client.set_games(
+                &root,
+                &vec![&env, games[1].clone(), games[2].clone()],
+                &vec![&env, proof(1), proof(2)]
+            ),
+            2
+        );
+        assert_eq!(client.get_game(&2), games[1]);
+        assert_eq!(client.get_game(&3), games[2]);
+        assert_eq!(client.get_game_settings(&3), vec![&env, 3]);
+    }
+    #[test]
+    fn test_assigned_ids() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, adm_usd, adm_trust) =
+            create_test_env();
+
+        let game_id = 1;
+        let game = Game {
+            id: game_id,
+            startTime: 1000,
+            endTime: 2000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex);
+        // games can't take the ids kept for the settings
+        let mut high = game.clone();
+        high.id = FIRST_SETTING_ID;
+        let (envelope, encoded) = signed_message(&env, &client, high.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        assert!(client
+            .try_set_game(&high, &ADMIN_SIGNER, &envelope, &signaturex)
+            .is_err());
+
+        // the id of a private setting is assigned whatever the caller sends
+        let user2 = Address::generate(&env);
+        adm_usd.mint(&user2, &100_000_000);
+        adm_trust.mint(&user2, &100_000_000);
+        let privateSetting = PrivateBet {
+            id: game_id,
+            gameid: game_id,
+            active: false,
+            settingAdmin: user.clone(),
+            description: String::from_str(&env, "Private Bet 1"),
+            amount_bet_min: 100,
+            amount_bet_max: 0,
+            fixed_stake: false,
+            users_invated: vec![&env, user.clone(), user2.clone()],
+            market: MarketType::Winner,
+        };
+        let setting = client.set_private_bet(&user, &privateSetting, &game_id);
+        assert_eq!(setting, FIRST_SETTING_ID);
+        assert_eq!(client.get_setting(&setting).id, setting);
+        let mut otherSetting = privateSetting.clone();
+        otherSetting.settingAdmin = user2.clone();
+        assert_eq!(
+            client.set_private_bet(&user2, &otherSetting, &game_id),
+            FIRST_SETTING_ID + 1
+        );
+        assert_eq!(client.get_id_owner(&IdKind::Setting, &setting), Some(user.clone()));
+        assert_eq!(
+            client.get_id_owner(&IdKind::Setting, &(FIRST_SETTING_ID + 1)),
+            Some(user2.clone())
+        );
+        assert_eq!(client.get_id_owner(&IdKind::Setting, &(FIRST_SETTING_ID + 2)), None);
+
+        // public settings of the admin take the next id too, the one of the game stays the game id
+        let publicSetting = PublicBet {
+            id: game_id,
+            gameid: game_id,
+            active: false,
+            description: String::from_str(&env, "Totals 2.5"),
+            market: MarketType::Totals(250),
+        };
+        let publicId = client.set_public_bet(&admin, &publicSetting, &game_id);
+        assert_eq!(publicId, FIRST_SETTING_ID + 2);
+        assert_eq!(client.get_public_setting(&publicId).id, publicId);
+        assert_eq!(client.get_public_setting(&game_id).id, game_id);
+        assert_eq!(client.get_id_owner(&IdKind::Setting, &publicId), Some(admin.clone()));
+
+        // every new position gets the next bet id, a top up keeps it
+        let bet = Bet {
+            id: 7,
+            Setting: setting,
+            bet: WINNER_LOCAL,
+            amount_bet: 1000,
+            betType: BetType::Private,
+            gameid: game_id,
+        };
+        assert_eq!(client.bet(&user, &bet), 1);
+        assert_eq!(client.bet(&user, &bet), 1);
+        let mut away = bet.clone();
+        away.bet = WINNER_AWAY;
+        assert_eq!(client.bet(&user2, &away), 2);
+        let mut public = bet.clone();
+        public.Setting = game_id;
+        public.betType = BetType::Public;
+        assert_eq!(client.bet(&user2, &public), 3);
+        assert_eq!(client.get_bet(&user, &setting).get(0).unwrap().id, 1);
+        assert_eq!(client.get_bet(&user, &setting).get(0).unwrap().amount_bet, 2000);
+        assert_eq!(client.get_id_owner(&IdKind::Bet, &1), Some(user.clone()));
+        assert_eq!(client.get_id_owner(&IdKind::Bet, &2), Some(user2.clone()));
+        assert_eq!(client.get_id_owner(&IdKind::Bet, &3), Some(user2.clone()));
+    }
+    #[test]
+    fn test_admin_transfer_and_rotation() {
+        let (env, client, admin, key, pk, user, token_usd, token_trust, _, _, adm_usd, adm_trust) =
+            create_test_env();
+        set_ledger_timestamp(&env, 100);
+
+        // the admin role moves in two steps
+        let new_admin = Address::generate(&env);
+        assert!(client.try_propose_admin(&user, &new_admin).is_err());
+        assert!(client.try_accept_admin(&new_admin).is_err());
+        client.propose_admin(&admin, &new_admin);
+        assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+        assert_eq!(client.get_admin(), admin);
+        assert!(client.try_accept_admin(&user).is_err());
+        client.accept_admin(&new_admin);
+        assert_eq!(client.get_admin(), new_admin);
+        assert_eq!(client.get_pending_admin(), None);
+        // the previous admin has no rights anymore
+        assert!(client.try_set_stake_lock(&admin, &500).is_err());
+        client.set_stake_lock(&new_admin, &500);
+
+        // the signing key rotates after the timelock
+        let newKey = Keypair::generate(&mut StdRng::seed_from_u64(4));
+        let newPk = BytesN::<32>::from_array(&env, &newKey.public.to_bytes());
+        assert!(client.try_propose_pubkey(&admin, &newPk).is_err());
+        assert!(client.try_apply_pubkey().is_err());
+        let effective_at = client.propose_pubkey(&new_admin, &newPk);
+        assert_eq!(effective_at, 100 + ROTATION_TIMELOCK);
+        assert_eq!(client.get_pubkey_rotation().pubkey, newPk);
+        assert_eq!(
+            client.try_apply_pubkey(),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                BettingError::RotationTimelocked as u32
+            )))
+        );
+        let game = Game {
+            id: 1,
+            startTime: 100_000,
+            endTime: 200_000,
+            summiter: Address::generate(&env),
+            Checker: soroban_sdk::Vec::new(&env),
+            active: false,
+            league: 1,
+            description: String::from_str(&env, "Team A vs Team B"),
+            team_local: 33,
+            team_away: 44,
+        };
+        set_ledger_timestamp(&env, effective_at);
+        client.apply_pubkey();
+        assert_eq!(client.get_admin_pubkey(), newPk);
+        assert_eq!(client.get_pubkey_rotation().effective_at, 0);
+        // games signed with the old key are refused
+        let (envelope, encoded) = signed_message(&env, &client, game.clone().to_xdr(&env));
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &key.sign(encoded.as_slice()).to_bytes());
+        assert!(client
+            .try_set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex)
+            .is_err());
+        let signaturex: BytesN<64> =
+            BytesN::from_array(&env, &newKey.sign(encoded.as_slice()).to_bytes());
+        assert!(client.set_game(&game, &ADMIN_SIGNER, &envelope, &signaturex));
+
+        // and so does the supreme court
+        let court = Address::generate(&env);
+        assert!(client.try_propose_supreme_court(&user, &court).is_err());
+        let effective_at = client.propose_supreme_court(&new_admin, &court);
+        assert_eq!(client.get_court_rotation().supreme_court, court);
+        assert!(client.try_apply_supreme_court().is_err());
+        set_ledger_timestamp(&env, effective_at);
+        client.apply_supreme_court();
+        assert_eq!(client.get_supreme_court(), court);
+        assert_eq!(client.get_court_rotation().effective_at, 0);
+        assert!(client.try_apply_supreme_court().is_err());
+    }
 }
@@ -4,7 +4,7 @@ use core::{f32::consts::E, panic, result};
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short, token, vec,
     xdr::{ScVal, ToXdr, WriteXdr},
-    Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Vec,
+    Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, Vec,
 };
 
 #[contracttype]
@@ -29,11 +29,28 @@ pub struct ResultGame {
     pub gameid: i128,
     pub description: String,
     pub result: BetKey,
+    pub score: Score,
+    pub half_time: HalfTime,
+    // winning outcome index of the Custom settings of the game, by setting id
+    pub outcomes: Map<i128, u32>,
     pub pause: bool,
     pub distribution_executed: bool,
 }
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Score {
+    pub local: i128,
+    pub away: i128,
+}
+/// The half time score is optional, not every source reports it
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HalfTime {
+    NotReported,
+    Score(Score),
+}
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ResultAssessment {
     pub id: i128,
     pub gameid: i128,
@@ -43,7 +60,7 @@ pub struct ResultAssessment {
     pub UsersReject: Vec<Address>,
 }
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PrivateBet {
     pub id: i128,
     pub gameid: i128,
@@ -51,21 +68,27 @@ pub struct PrivateBet {
     pub settingAdmin: Address,
     pub description: String,
     pub amount_bet_min: i128,
+    // 0 means there is no maximum
+    pub amount_bet_max: i128,
+    // every user bets exactly amount_bet_min
+    pub fixed_stake: bool,
     pub users_invated: Vec<Address>,
+    pub market: MarketType,
 }
 #[contracttype]
 #[derive(Clone)]
 pub struct LastB {
     pub id: i128,
-    pub lastBet: BetKey,
+    pub lastBet: u32,
 }
 #[contracttype]
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PublicBet {
     pub id: i128,
     pub gameid: i128,
     pub active: bool,
     pub description: String,
+    pub market: MarketType,
 }
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -74,9 +97,204 @@ pub struct Bet {
     pub gameid: i128,
     pub betType: BetType,
     pub Setting: i128,
-    pub bet: BetKey,
+    pub bet: u32,
     pub amount_bet: i128,
 }
+/// The admin signs it to cancel a game before it starts
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameCancellation {
+    pub game_id: i128,
+    pub reason: String,
+}
+/// The admin signs it to move the times of a game, version is the number of the rescheduling
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameSchedule {
+    pub game_id: i128,
+    pub version: u32,
+    pub startTime: u32,
+    pub endTime: u32,
+    // select new summiters and checkers for the new date
+    pub reselect: bool,
+}
+/// Deadlines of a game in seconds after its end time, each window has to end after the previous one
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timeline {
+    // the main summiter summits the result
+    pub summit_window: u32,
+    // the checkers summit the result once the main summiter was fined
+    pub checker_window: u32,
+    // the admin summits the result, after it the bettors can claim a refund
+    pub admin_window: u32,
+    // the users and checkers assess the result
+    pub assessment_window: u32,
+}
+/// Economic parameters of the protocol, the percentages are over 100
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Params {
+    // cut of the gain pool for the protocol
+    pub protocol_fee: i128,
+    // cut of the gain pool shared by the honest summiters
+    pub summiter_fee: i128,
+    // cut of the gain pool shared by the honest summiters when nobody won, the protocol takes the rest
+    pub no_winner_summiter_fee: i128,
+    // part of the stake locked in trust tokens
+    pub trust_percentage: i128,
+    // part of the stake lost by the bettors who didn't assess the result
+    pub no_vote_penalty: i128,
+    // part of the stake kept when a bet is cancelled before kickoff
+    pub cancel_fee: i128,
+    // honesty points charged when betting and given back with the refund
+    pub bet_points: i128,
+    // honesty points won by an honest bettor when claiming
+    pub honest_points: i128,
+    // history points won or lost by the summiters and checkers
+    pub summiter_points: i128,
+}
+/// New parameters waiting for the timelock to end
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParamsProposal {
+    pub params: Params,
+    pub effective_at: u32,
+}
+/// A new game signing key waiting for the timelock
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PubkeyRotation {
+    pub pubkey: BytesN<32>,
+    pub effective_at: u32,
+}
+/// A new supreme court waiting for the timelock
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CourtRotation {
+    pub supreme_court: Address,
+    pub effective_at: u32,
+}
+/// The kinds of ids the contract assigns
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IdKind {
+    Setting,
+    Bet,
+    Result,
+    Parlay,
+}
+/// The last id assigned of each kind
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdCounters {
+    pub setting: i128,
+    pub bet: i128,
+    pub result: i128,
+    pub parlay: i128,
+}
+/// A key allowed to sign the games of some leagues, the admin can revoke it
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Publisher {
+    pub pubkey: BytesN<32>,
+    pub leagues: Vec<i128>,
+    pub revoked: bool,
+}
+/// Domain of an admin signature: the message signed is this envelope followed by the payload, so a signature
+/// only works once, on this contract and network, before the expiry ledger
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedEnvelope {
+    pub version: u32,
+    pub contract: Address,
+    pub network_id: BytesN<32>,
+    pub nonce: u64,
+    pub expiry_ledger: u32,
+}
+/// Randomness of the summiter draw of a game. The activation only records the entropy and its ledger, the draw
+/// is made by a later call from the pool of candidates taken at the activation: with the secret the admin committed
+/// to (and bonded) before the game was activated, or from the activation alone when there is no secret.
+/// The entropy is only shown once the draw is made, and the seed is kept so anyone can verify it
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectionDraw {
+    pub commitment: BytesN<32>,
+    pub entropy: BytesN<32>,
+    pub seed: BytesN<32>,
+    pub drawn: bool,
+    pub reveal_deadline: u32,
+    pub activated_ledger: u32,
+    pub bond: i128,
+    pub pool: Vec<(Address, i128)>,
+}
+/// State of a summiter: the games its stake is locked for, its exit and its place in the leaderboard
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SummiterBond {
+    pub games: Vec<i128>,
+    pub unbonding: bool,
+    pub available_at: u32,
+    pub last_selected: u32,
+    pub listed: bool,
+    pub score: i128,
+}
+/// Pages of the leaderboard in descending score order, as (page id, lowest score, size)
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeaderboardIndex {
+    pub pages: Vec<(u32, i128, u32)>,
+    pub next_page: u32,
+    pub total: u32,
+}
+/// How the summiter and the checkers of a game are drawn from the leaderboard
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectionPolicy {
+    // best ranked candidates the draw is made from
+    pub pool_size: u32,
+    pub checkers: u32,
+    // one more checker each time the value bet on the game before its draw reaches it, 0 keeps the checkers fixed
+    pub value_per_checker: i128,
+    pub max_checkers: u32,
+    // seconds an address waits after being selected before it can be drawn again
+    pub exclusion_window: u32,
+    // minimum summiter history of the candidates
+    pub min_history: i128,
+}
+/// Which games a timeline applies to, a game timeline overrides its league one
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimelineScope {
+    Default,
+    League(i128),
+    Game(i128),
+}
+/// A leg of a parlay, the outcome index of a public setting
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParlayLeg {
+    pub setting: i128,
+    pub outcome: u32,
+}
+/// A parlay only pays if every leg wins, each leg multiplies the stake by its pool odds
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Parlay {
+    pub id: i128,
+    pub legs: Vec<ParlayLeg>,
+    pub amount_bet: i128,
+    pub claimed: bool,
+}
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingPools {
+    pub setting: i128,
+    pub distributed: bool,
+    pub winnerPool: i128,
+    pub loserPool: i128,
+    pub pool: i128,
+}
 
 #[derive(Clone)]
 #[contracttype]
@@ -87,7 +305,7 @@ pub enum DataKey {
     TotalBet(i128),
     Result(i128),
     HonestyPoints(Address),
-    ClaimWinner(Address),
+    BetPlacedAt(i128),
     ClaimSummiter(Address),
     ClaimProtocol,
     ClaimProtocolTrust,
@@ -101,19 +319,36 @@ pub enum DataKey {
     SetPublicBet(i128),
     Bet(Address, i128),
     PrivateBetList(i128),
+    PublicBetList(i128),
     lastBet(i128),
     Fine(i128),
     FinesApplied(i128),
     ListBetUser(i128),
-    NotAssesedYet(i128, BetKey),
-    Rejected(i128, BetKey),
-    Approved(i128, BetKey),
+    NotAssesedYet(i128, u32),
+    Rejected(i128, u32),
+    Approved(i128, u32),
     pool(i128),
     poolSummiter(i128),
     Complain(i128),
     winnerPool(i128),
     loserPool(i128),
     UserWithdraw(i128, Address),
+    SettingDistributed(i128),
+    Parlay(Address, i128),
+    ParlayPool,
+    GameCancelled(i128),
+    GameReschedules(i128),
+    Timeline,
+    LeagueTimeline(i128),
+    GameTimeline(i128),
+    GameParams(i128),
+    SummiterBond(Address),
+    SelectionDraw(i128),
+    LeaderboardPage(u32),
+    SignatureNonce(u32, u64),
+    Publisher(u32),
+    FixtureRoot(BytesN<32>),
+    IdOwner(IdKind, i128),
 }
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[contracttype]
@@ -123,6 +358,35 @@ pub enum BetKey {
     Draw,
     Cancel,
 }
+/// Bets pick an outcome by its index in the market
+/// Winner is the 1X2 market (0 local, 1 draw, 2 away)
+/// Totals bets over (0) or under (1) a line of goals given in hundredths (250 = 2.5 goals)
+/// Handicap adds a line in hundredths to the local team score (-150 = local -1.5), quarter lines
+/// (-25, -75...) split the stake in two halves (0 local, 1 away)
+/// DrawNoBet bets on the local (0) or away (1) team and refunds the stakes on a draw
+/// ExactScore bets on one of the listed scores, the last index is any other score
+/// Custom has N named outcomes and the result of the game tells which one won
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum MarketType {
+    Winner,
+    Totals(i128),
+    Handicap(i128),
+    DrawNoBet,
+    ExactScore(Vec<Score>),
+    Custom(Vec<String>),
+}
+/// How a bet was settled, half results come from quarter handicap lines
+/// The variants are ordered from the best settlement to the worst
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[contracttype]
+pub enum Settlement {
+    Win,
+    HalfWin,
+    Push,
+    HalfLoss,
+    Loss,
+}
 #[derive(Clone, PartialEq, Eq)]
 #[contracttype]
 pub enum AssessmentKey {
@@ -142,6 +406,28 @@ pub enum ClaimType {
     Protocol,
     User,
 }
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum UserKind {
+    WinnerHonest,
+    LoserHonest,
+    Dishonest,
+    WinnerNoVote,
+    LoserNoVote,
+    NoBet,
+}
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimPreview {
+    pub setting: i128,
+    pub kind: UserKind,
+    // the best settlement among the positions of the user
+    pub settlement: Settlement,
+    pub amountUsd: i128,
+    pub amountTrust: i128,
+    pub points: i128,
+    pub claimed: bool,
+}
 #[contracttype]
 #[derive(Clone)]
 struct Summiter {